@@ -1,64 +1,576 @@
-use std::fmt::Debug;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Debug,
+};
+
+mod algo;
+mod csv;
+mod dot;
+mod generate;
+mod json;
+mod typed;
+
+pub use algo::{
+    bfs, canonical_form, coarsen, complement_within, connected_components, degree_assortativity,
+    filter_edges, infer_directedness, longest_path_dag, maximal_cliques, random_walk,
+    shortest_path, shortest_weighted_path, spanning_forest, to_incidence_matrix, topological_sort,
+    MAX_CLIQUE_NODES,
+};
+pub use csv::{from_csv, CsvError};
+pub use dot::{from_dot, to_dot, DotError};
+pub use generate::gnp;
+pub use json::GraphData;
+#[cfg(feature = "serde")]
+pub use json::{from_json, to_json, JsonError, MAX_JSON_NODES};
+pub use typed::TypedGraph;
 
 pub trait Graph: Debug {
+    /// One past the largest node index, nodes are `0..dot_count()` on every backend.
+    ///
+    /// Covers both the nodes the graph was created with and every node an edge refers to,
+    /// so indices skipped by edges still count as isolated nodes.
     fn dot_count(&self) -> usize;
 
     fn for_each_edge(&self, cb: &mut dyn FnMut(usize, usize));
 
     fn add_edge(&mut self, from: usize, to: usize);
+
+    /// Adds an isolated node after the existing ones and returns its index, the old `dot_count()`.
+    fn add_node(&mut self) -> usize;
+
+    /// Adds every edge in order, same as calling `add_edge` for each.
+    fn add_edges(&mut self, edges: &[(usize, usize)]) {
+        for &(from, to) in edges {
+            self.add_edge(from, to)
+        }
+    }
+
+    /// Removes one edge going from `from` to `to`, returns whether there was one.
+    ///
+    /// Only the earliest added of parallel edges is removed on every backend,
+    /// nodes stay in the graph.
+    fn remove_edge(&mut self, from: usize, to: usize) -> bool;
+
+    /// Removes `node` with every edge touching it, does nothing for nodes the graph doesn't have.
+    ///
+    /// Nodes after it are renumbered one down, so nodes stay `0..dot_count()` without gaps
+    /// on every backend.
+    fn remove_node(&mut self, node: usize);
+
+    /// Checks backend invariants in debug builds, does nothing in release.
+    fn assert_valid(&self);
+
+    /// Whether edges are one-way, see `GraphOptions::directedness`.
+    fn directedness(&self) -> Directedness {
+        Directedness::Directed
+    }
+
+    /// Largest node index an edge refers to, `None` without edges.
+    ///
+    /// Unlike `dot_count` it ignores nodes that only exist because the graph was created with them.
+    fn max_node_index(&self) -> Option<usize> {
+        let mut max = None;
+        self.for_each_edge(&mut |from, to| max = max.max(Some(from.max(to))));
+        max
+    }
+
+    /// Nodes of the weakly connected component containing `node`, in ascending order.
+    fn component_of(&self, node: usize) -> Vec<usize> {
+        let mut adjacent: HashMap<usize, Vec<usize>> = HashMap::new();
+        self.for_each_edge(&mut |from, to| {
+            adjacent.entry(from).or_default().push(to);
+            adjacent.entry(to).or_default().push(from);
+        });
+
+        let mut visited = HashSet::from([node]);
+        let mut queue = VecDeque::from([node]);
+        while let Some(current) = queue.pop_front() {
+            for &next in adjacent.get(&current).into_iter().flatten() {
+                if visited.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut component: Vec<_> = visited.into_iter().collect();
+        component.sort_unstable();
+        component
+    }
+
+    /// Out-neighbors of every node in ascending order, indexed by node.
+    ///
+    /// Covers `0..dot_count()` and any larger node referenced by an edge,
+    /// parallel edges show up as repeated neighbors. Undirected edges are listed at both ends.
+    fn adjacency_list(&self) -> Vec<Vec<usize>> {
+        let mut adjacency = vec![vec![]; self.dot_count()];
+        self.for_each_arc(&mut |from, to| {
+            let required = from.max(to) + 1;
+            if adjacency.len() < required {
+                adjacency.resize_with(required, Vec::new);
+            }
+            adjacency[from].push(to);
+        });
+
+        for neighbors in adjacency.iter_mut() {
+            neighbors.sort_unstable();
+        }
+        adjacency
+    }
+
+    /// Whether there's an edge going from `from` to `to`, `false` for nodes the graph doesn't have.
+    ///
+    /// Undirected edges go both ways.
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        let mut found = false;
+        self.for_each_arc(&mut |arc_from, arc_to| found |= (arc_from, arc_to) == (from, to));
+        found
+    }
+
+    /// Calls `cb` with the target of every edge going out of `node`, once per parallel edge.
+    ///
+    /// Undirected edges go both ways, a self-loop is reported once.
+    fn neighbors(&self, node: usize, cb: &mut dyn FnMut(usize)) {
+        self.for_each_arc(&mut |from, to| {
+            if from == node {
+                cb(to)
+            }
+        });
+    }
+
+    /// Calls `cb` with every edge in each direction it can be followed.
+    ///
+    /// Same as `for_each_edge` for directed graphs. Undirected edges are reported both ways,
+    /// except for self-loops, which are still reported once.
+    fn for_each_arc(&self, cb: &mut dyn FnMut(usize, usize)) {
+        let undirected = self.directedness() == Directedness::Undirected;
+        self.for_each_edge(&mut |from, to| {
+            cb(from, to);
+            if undirected && from != to {
+                cb(to, from);
+            }
+        });
+    }
+
+    /// Number of edge ends at `node`, parallel edges included.
+    ///
+    /// A self-loop counts twice, once per end, so degrees add up to twice the edge count.
+    /// For a directed graph it's `in_degree + out_degree`.
+    fn degree(&self, node: usize) -> usize {
+        let mut degree = 0;
+        self.for_each_edge(&mut |from, to| {
+            degree += (from == node) as usize + (to == node) as usize
+        });
+        degree
+    }
+
+    /// Number of edges going out of `node`, a self-loop counts once.
+    ///
+    /// Undirected edges go both ways, so for an undirected graph it's `degree`.
+    fn out_degree(&self, node: usize) -> usize {
+        if self.directedness() == Directedness::Undirected {
+            return self.degree(node);
+        }
+        let mut degree = 0;
+        self.for_each_edge(&mut |from, _| degree += (from == node) as usize);
+        degree
+    }
+
+    /// Number of edges coming into `node`, a self-loop counts once.
+    ///
+    /// Undirected edges go both ways, so for an undirected graph it's `degree`.
+    fn in_degree(&self, node: usize) -> usize {
+        if self.directedness() == Directedness::Undirected {
+            return self.degree(node);
+        }
+        let mut degree = 0;
+        self.for_each_edge(&mut |_, to| degree += (to == node) as usize);
+        degree
+    }
+
+    /// Every edge going from `from` to `to`, once per parallel edge.
+    fn edges_between(&self, from: usize, to: usize) -> Vec<(usize, usize)> {
+        let mut edges = vec![];
+        self.for_each_edge(&mut |edge_from, edge_to| {
+            if (edge_from, edge_to) == (from, to) {
+                edges.push((edge_from, edge_to))
+            }
+        });
+        edges
+    }
+
+    /// Number of parallel edges going from `from` to `to`, 0 without an edge.
+    ///
+    /// Every backend follows `GraphOptions::multiplicity`: at most 1 for `Multiplicity::Simple`,
+    /// one per `add_edge` call for `Multiplicity::Multi`.
+    fn edge_multiplicity(&self, from: usize, to: usize) -> usize {
+        self.edges_between(from, to).len()
+    }
+
+    /// Whether `to` can be reached from `from` following edges in their direction,
+    /// undirected edges can be followed either way.
+    ///
+    /// Nodes outside of `0..dot_count()` are unreachable, any other node reaches itself.
+    fn path_exists(&self, from: usize, to: usize) -> bool {
+        let count = self.dot_count();
+        if from >= count || to >= count {
+            return false;
+        }
+        if from == to {
+            return true;
+        }
+
+        let mut outgoing: HashMap<usize, Vec<usize>> = HashMap::new();
+        self.for_each_arc(&mut |from, to| outgoing.entry(from).or_default().push(to));
+
+        let mut visited = HashSet::from([from]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(current) = queue.pop_front() {
+            for &next in outgoing.get(&current).into_iter().flatten() {
+                if next == to {
+                    return true;
+                }
+                if visited.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        false
+    }
+}
+
+pub trait WeightedGraph: Graph {
+    /// Adds an edge with the given weight, `add_edge` is the same with weight 1.0.
+    ///
+    /// With `Multiplicity::Simple` an edge that's already there keeps its weight.
+    fn add_weighted_edge(&mut self, from: usize, to: usize, weight: f32);
+
+    /// Same edges as `for_each_edge`, in the same order, along with their weights.
+    fn for_each_weighted_edge(&self, cb: &mut dyn FnMut(usize, usize, f32)) {
+        self.for_each_edge(&mut |from, to| cb(from, to, 1.0))
+    }
+
+    /// Weights of every edge going from `from` to `to`, once per parallel edge.
+    ///
+    /// Undirected edges match in either direction.
+    fn weights_between(&self, from: usize, to: usize) -> Vec<f32> {
+        let undirected = self.directedness() == Directedness::Undirected;
+        let mut weights = vec![];
+        self.for_each_weighted_edge(&mut |edge_from, edge_to, weight| {
+            let reversed = undirected && (edge_to, edge_from) == (from, to);
+            if (edge_from, edge_to) == (from, to) || reversed {
+                weights.push(weight)
+            }
+        });
+        weights
+    }
 }
 
-pub trait DrawableGraph : Graph {
-    fn draw(&self, backend: crate::gui::DrawBackend) {
-        let mut api = crate::gui::DrawingApi::default();
-        let dot_coords: Vec<_> = (0..self.dot_count())
-            .map(|_| api.draw_dot())
-            .collect();
+pub trait DrawableGraph : WeightedGraph {
+    /// Lays the graph out and shows or exports it with `backend`.
+    ///
+    /// Fails the way `to_holder` and `gui::draw_holder` do.
+    fn draw(
+        &self,
+        backend: crate::gui::DrawBackend,
+        config: &crate::gui::RenderConfig,
+    ) -> Result<crate::gui::DrawOutcome, crate::gui::DrawError> {
+        let holder = self.to_holder(config)?;
+        crate::gui::draw_holder(holder, config, backend)
+    }
+
+    /// Draws the graph the way `draw` does, then removes the edges removed in the window from it.
+    fn edit(
+        &mut self,
+        backend: crate::gui::DrawBackend,
+        config: &crate::gui::RenderConfig,
+    ) -> Result<crate::gui::DrawOutcome, crate::gui::DrawError> {
+        let outcome = self.draw(backend, config)?;
+        if let crate::gui::DrawOutcome::Shown { removed_edges } = &outcome {
+            for &(from, to) in removed_edges {
+                self.remove_edge(from, to);
+            }
+        }
+        Ok(outcome)
+    }
+
+    /// Position of every node in the unit square as `draw` places it with `config`,
+    /// fitted to `RenderConfig::fit_margin` too, computed without drawing anything.
+    fn layout(&self, config: &crate::gui::RenderConfig) -> Vec<crate::gui::Coord> {
+        place_all(self.as_weighted(), config).coords
+    }
+
+    /// Draws the graph with every node at its position in `coords` instead of laying it out.
+    ///
+    /// Positions are used as given, without fitting them to the canvas.
+    /// Fails when there isn't exactly one position per node, otherwise the way `draw` does.
+    fn draw_with_positions(
+        &self,
+        coords: &[crate::gui::Coord],
+        backend: crate::gui::DrawBackend,
+        config: &crate::gui::RenderConfig,
+    ) -> Result<crate::gui::DrawOutcome, crate::gui::DrawError> {
+        let config = config
+            .clone()
+            .layout(crate::layout::Layout::Fixed(coords.to_vec()))
+            .fit_margin(None);
+        self.draw(backend, &config)
+    }
+
+    /// Picture `draw` shows, built without opening a window.
+    ///
+    /// Fails when `config` refers to a node outside of `0..dot_count()`
+    /// or a `Layout::Fixed` has a position count other than `dot_count()`.
+    fn to_holder(
+        &self,
+        config: &crate::gui::RenderConfig,
+    ) -> Result<crate::gui::GraphicsHolder, crate::gui::DrawError> {
+        let dot_count = self.dot_count();
+        let root = match config.layout {
+            crate::layout::Layout::SpanningTree { root, .. } => Some(root),
+            _ => None,
+        };
+        if let Some(&node) = config.path.iter().chain(&root).find(|node| **node >= dot_count) {
+            return Err(crate::gui::DrawError::NodeIndexOutOfRange { node, dot_count });
+        }
+        if let crate::layout::Layout::Fixed(coords) = &config.layout {
+            if coords.len() != dot_count {
+                return Err(crate::gui::DrawError::PositionCountMismatch {
+                    positions: coords.len(),
+                    dot_count,
+                });
+            }
+        }
+
+        let directed = self.directedness() == Directedness::Directed;
+        let config = &config.clone().arrows(config.arrows && directed);
+        let mut api = crate::gui::DrawingApi::new(config);
+        let placement = place_all(self.as_weighted(), config);
+        let dot_count = placement.coords.len();
+        for coord in placement.coords {
+            api.draw_dot_at(coord);
+        }
+        if config.size_by_degree {
+            // Same counting as `Graph::degree`, in a single pass over the edges.
+            let mut degrees = vec![0; dot_count];
+            self.for_each_edge(&mut |from, to| {
+                degrees[from] += 1;
+                degrees[to] += 1;
+            });
+            let base = config.effective_dot_style().radius;
+            for (idx, degree) in degrees.into_iter().enumerate() {
+                api.size_dot(idx, crate::gui::degree_radius(base, degree));
+            }
+        }
+        if config.color_components {
+            for (idx, component) in connected_components(self.as_graph()).into_iter().enumerate() {
+                api.color_dot(idx, crate::gui::component_color(component));
+            }
+        }
+
+        // Edges connecting the same two nodes, in either direction, are bent apart.
+        let mut pair_bends: HashMap<_, Vec<f32>> = HashMap::new();
+        let mut weighted = false;
+        self.for_each_weighted_edge(&mut |from, to, weight| {
+            pair_bends.entry((from.min(to), from.max(to))).or_default().push(0.0);
+            weighted |= weight != 1.0;
+        });
+        for bends in pair_bends.values_mut() {
+            // Reversed, so edges can pop their bends in `for_each_edge` order.
+            *bends = crate::gui::parallel_bends(bends.len()).rev().collect();
+        }
+
+        let path: HashSet<_> = config.path.windows(2).map(|pair| (pair[0], pair[1])).collect();
+        let on_path = |from, to| {
+            path.contains(&(from, to)) || (!directed && path.contains(&(to, from)))
+        };
+        // Path edges go last so other edges don't cross over them.
+        let mut path_lines = vec![];
+
+        let mut styles = placement.edge_styles.into_iter();
+        self.for_each_weighted_edge(&mut |from, to, weight| {
+            let bend = pair_bends
+                .get_mut(&(from.min(to), from.max(to)))
+                .and_then(Vec::pop)
+                .unwrap_or(0.0);
+            // Bends are given for the direction from the smaller node.
+            let bend = if from <= to { bend } else { -bend };
+            match styles.next() {
+                _ if on_path(from, to) => {
+                    path_lines.push((from, to, bend));
+                    return;
+                }
+                Some(style) => api.draw_styled_edge(from, to, style),
+                None if weighted => api.draw_weighted_edge(from, to, weight),
+                None => api.draw_edge(from, to),
+            }
+            api.bend_last_edge(bend);
+        });
+        for (from, to, bend) in path_lines {
+            api.draw_styled_edge(from, to, crate::gui::PATH_EDGE_STYLE);
+            api.bend_last_edge(bend);
+        }
+        Ok(api.into_holder())
+    }
+
+    fn as_graph(&self) -> &dyn Graph;
+
+    fn as_weighted(&self) -> &dyn WeightedGraph;
+}
 
-        self.for_each_edge(&mut |from, to| api.draw_edge(dot_coords[from], dot_coords[to]));
-        api.draw_with(backend);
+/// Placement by `config.layout` covering every node an edge refers to, fitted to the view
+/// unless `config.fit_margin` is `None`.
+fn place_all(
+    graph: &dyn WeightedGraph,
+    config: &crate::gui::RenderConfig,
+) -> crate::layout::Placement {
+    let mut placement = config.layout.place(graph);
+    // Backends outside of this crate may have edges past `dot_count()`, place those nodes too.
+    let bound = graph.max_node_index().map_or(0, |max| max + 1);
+    if placement.coords.len() < bound {
+        crate::random::with_rng(|gen| {
+            use rand::Rng;
+            placement.coords.resize_with(bound, || {
+                crate::gui::Coord::new(gen.gen_range(0.0..1.0), gen.gen_range(0.0..1.0))
+            })
+        });
     }
+    if let Some(margin) = config.fit_margin {
+        placement.coords = crate::layout::fit_to_view(&placement.coords, margin);
+    }
+    placement
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum GraphBackend {
     EdgeList,
     Matrix,
+    AdjacencyList,
 }
 
 impl clap::ValueEnum for GraphBackend {
     fn value_variants<'a>() -> &'a [Self] {
-        &[Self::EdgeList, Self::Matrix]
+        &[Self::EdgeList, Self::Matrix, Self::AdjacencyList]
     }
 
     fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
         Some(clap::builder::PossibleValue::new(match self {
             GraphBackend::EdgeList => "edges",
             GraphBackend::Matrix => "matrix",
+            GraphBackend::AdjacencyList => "adjacency",
         }))
     }
 }
 
+/// Whether adding an edge that's already there makes a parallel edge or does nothing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Multiplicity {
+    #[default]
+    Simple,
+    Multi,
+}
+
+/// Whether `add_edge(a, b)` also connects `b` to `a`.
+///
+/// Undirected graphs report every edge once from `for_each_edge`, smaller end first,
+/// while `has_edge`, `neighbors` and `remove_edge` accept either direction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Directedness {
+    #[default]
+    Directed,
+    Undirected,
+}
+
+/// Semantics a graph is built with, the same for every backend.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GraphOptions {
+    pub multiplicity: Multiplicity,
+    pub directedness: Directedness,
+    /// Keep out-neighbors per node so edge-list queries about a node take O(degree)
+    /// instead of a scan over all edges, at the cost of storing every edge twice.
+    /// Backends with cheap lookups ignore it.
+    pub neighbor_index: bool,
+}
+
+impl GraphOptions {
+    /// How an edge is stored, undirected edges always start at the smaller end.
+    fn edge_key(&self, from: usize, to: usize) -> (usize, usize) {
+        match self.directedness {
+            Directedness::Directed => (from, to),
+            Directedness::Undirected => (from.min(to), from.max(to)),
+        }
+    }
+}
+
 pub fn with_dots_count(backend: GraphBackend, count: usize) -> Box<dyn DrawableGraph> {
+    with_options(backend, count, GraphOptions::default())
+}
+
+pub fn with_options(
+    backend: GraphBackend,
+    count: usize,
+    options: GraphOptions,
+) -> Box<dyn DrawableGraph> {
     match backend {
-        GraphBackend::EdgeList => Box::new(edge_list::EdgeListGraph::with_dots_count(count)),
-        GraphBackend::Matrix => Box::new(matrix::MatrixGraph::with_dots_count(count)),
+        GraphBackend::EdgeList => Box::new(edge_list::EdgeListGraph::with_options(count, options)),
+        GraphBackend::Matrix => Box::new(matrix::MatrixGraph::with_options(count, options)),
+        GraphBackend::AdjacencyList => {
+            Box::new(adjacency::AdjacencyListGraph::with_options(count, options))
+        }
     }
 }
 
+/// Copy of `src` stored in the `target` backend, with the same nodes, edges and directedness.
+///
+/// Only uses `Graph` methods, so weights are not carried over. The copy allows parallel
+/// edges if `src` has any.
+pub fn convert(src: &dyn Graph, target: GraphBackend) -> Box<dyn DrawableGraph> {
+    let mut edges = vec![];
+    src.for_each_edge(&mut |from, to| edges.push((from, to)));
+
+    let mut distinct = edges.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+    let multiplicity = if distinct.len() < edges.len() {
+        Multiplicity::Multi
+    } else {
+        Multiplicity::Simple
+    };
+
+    let options = GraphOptions {
+        multiplicity,
+        directedness: src.directedness(),
+        ..Default::default()
+    };
+    let mut graph = with_options(target, src.dot_count(), options);
+    graph.add_edges(&edges);
+    graph
+}
+
 mod edge_list {
 
-    use eframe::epaint::ahash::HashSet;
+    use eframe::epaint::ahash::{HashMap, HashSet};
+
+    use super::{Directedness, GraphOptions, Multiplicity};
 
     #[derive(Debug, Default)]
     pub struct EdgeListGraph {
-        dots: HashSet<usize>,
+        /// One past the largest node, created or referenced by an edge.
+        dot_count: usize,
+        /// Undirected edges are stored once, smaller end first.
         edges: Vec<(usize, usize)>,
+        /// Weight of every edge in `edges`.
+        weights: Vec<f32>,
+        options: GraphOptions,
+        /// Edges already added, only tracked for `Multiplicity::Simple` to skip duplicates.
+        present: HashSet<(usize, usize)>,
+        /// Neighbors of every node with edges, only kept with `GraphOptions::neighbor_index`.
+        /// Undirected edges are listed at both ends.
+        outgoing: Option<HashMap<usize, Vec<usize>>>,
     }
 
     impl super::Graph for EdgeListGraph {
         fn dot_count(&self) -> usize {
-            self.dots.len()
+            self.dot_count
         }
 
         fn for_each_edge(&self, cb: &mut dyn FnMut(usize, usize)) {
@@ -68,68 +580,1149 @@ mod edge_list {
         }
 
         fn add_edge(&mut self, from: usize, to: usize) {
-            self.dots.insert(from);
-            self.dots.insert(to);
-            self.edges.push((from, to));
+            self.push_edge(from, to, 1.0)
+        }
+
+        fn add_node(&mut self) -> usize {
+            self.dot_count += 1;
+            self.dot_count - 1
+        }
+
+        fn directedness(&self) -> Directedness {
+            self.options.directedness
+        }
+
+        fn has_edge(&self, from: usize, to: usize) -> bool {
+            let edge = self.options.edge_key(from, to);
+            if self.options.multiplicity == Multiplicity::Simple {
+                return self.present.contains(&edge);
+            }
+            match &self.outgoing {
+                Some(outgoing) => outgoing.get(&from).is_some_and(|next| next.contains(&to)),
+                None => self.edges.contains(&edge),
+            }
+        }
+
+        fn neighbors(&self, node: usize, cb: &mut dyn FnMut(usize)) {
+            let undirected = self.options.directedness == Directedness::Undirected;
+            match &self.outgoing {
+                Some(outgoing) => outgoing.get(&node)
+                    .into_iter()
+                    .flatten()
+                    .for_each(|to| cb(*to)),
+                None => self.edges.iter()
+                    .for_each(|&(from, to)| {
+                        if from == node {
+                            cb(to)
+                        } else if undirected && to == node {
+                            cb(from)
+                        }
+                    }),
+            }
+        }
+
+        fn out_degree(&self, node: usize) -> usize {
+            match self.options.directedness {
+                Directedness::Directed => {
+                    self.edges.iter().filter(|(from, _)| *from == node).count()
+                }
+                Directedness::Undirected => super::Graph::degree(self, node),
+            }
+        }
+
+        fn remove_edge(&mut self, from: usize, to: usize) -> bool {
+            let edge = self.options.edge_key(from, to);
+            let Some(idx) = self.edges.iter().position(|present| *present == edge) else {
+                return false;
+            };
+            self.edges.remove(idx);
+            self.weights.remove(idx);
+            self.present.remove(&edge);
+
+            let undirected = self.options.directedness == Directedness::Undirected;
+            if let Some(outgoing) = &mut self.outgoing {
+                let mut unlink = |from, to| {
+                    let next = outgoing.entry(from).or_default();
+                    if let Some(idx) = next.iter().position(|next| *next == to) {
+                        next.remove(idx);
+                    }
+                };
+                unlink(from, to);
+                if undirected && from != to {
+                    unlink(to, from);
+                }
+            }
+            true
+        }
+
+        fn remove_node(&mut self, node: usize) {
+            if node >= self.dot_count {
+                return;
+            }
+
+            let edges = std::mem::take(&mut self.edges);
+            let weights = std::mem::take(&mut self.weights);
+            self.present.clear();
+            if let Some(outgoing) = &mut self.outgoing {
+                outgoing.clear();
+            }
+            self.dot_count -= 1;
+            let shift = |idx: usize| idx - (idx > node) as usize;
+            for ((from, to), weight) in edges.into_iter().zip(weights) {
+                if from != node && to != node {
+                    self.push_edge(shift(from), shift(to), weight);
+                }
+            }
+        }
+
+        fn edges_between(&self, from: usize, to: usize) -> Vec<(usize, usize)> {
+            let Some(outgoing) = &self.outgoing else {
+                let edge = self.options.edge_key(from, to);
+                return self.edges.iter()
+                    .filter(|present| **present == edge)
+                    .map(|_| (from, to))
+                    .collect();
+            };
+            outgoing.get(&from)
+                .into_iter()
+                .flatten()
+                .filter(|next| **next == to)
+                .map(|_| (from, to))
+                .collect()
+        }
+
+        fn assert_valid(&self) {
+            if cfg!(debug_assertions) {
+                let undirected = self.options.directedness == Directedness::Undirected;
+                for (from, to) in self.edges.iter() {
+                    assert!(*from < self.dot_count, "edge source {from} is not a known dot");
+                    assert!(*to < self.dot_count, "edge target {to} is not a known dot");
+                    assert!(!undirected || from <= to, "undirected edge ({from}, {to}) is flipped");
+                }
+                assert_eq!(self.weights.len(), self.edges.len(), "edges and weights are out of sync");
+                if let Some(outgoing) = &self.outgoing {
+                    let indexed: usize = outgoing.values().map(Vec::len).sum();
+                    let expected: usize = self.edges.iter()
+                        .map(|(from, to)| if undirected && from != to { 2 } else { 1 })
+                        .sum();
+                    assert_eq!(indexed, expected, "neighbor index is out of sync");
+                }
+            }
         }
     }
 
-    impl super::DrawableGraph for EdgeListGraph {}
+    impl super::WeightedGraph for EdgeListGraph {
+        fn add_weighted_edge(&mut self, from: usize, to: usize, weight: f32) {
+            self.push_edge(from, to, weight)
+        }
+
+        fn for_each_weighted_edge(&self, cb: &mut dyn FnMut(usize, usize, f32)) {
+            self.edges.iter()
+                .zip(self.weights.iter())
+                .for_each(|(&(from, to), &weight)| cb(from, to, weight))
+        }
+    }
+
+    impl super::DrawableGraph for EdgeListGraph {
+        fn as_graph(&self) -> &dyn super::Graph {
+            self
+        }
+
+        fn as_weighted(&self) -> &dyn super::WeightedGraph {
+            self
+        }
+    }
 
     impl EdgeListGraph {
-        pub fn with_dots_count(count: usize) -> Self {
+        pub fn with_options(count: usize, options: GraphOptions) -> Self {
             Self {
-                dots: (0..count).collect(),
-                edges: vec![],
+                dot_count: count,
+                options,
+                outgoing: options.neighbor_index.then(HashMap::default),
+                ..Default::default()
+            }
+        }
+
+        fn push_edge(&mut self, from: usize, to: usize, weight: f32) {
+            self.dot_count = self.dot_count.max(from.max(to) + 1);
+            let edge = self.options.edge_key(from, to);
+            let simple = self.options.multiplicity == Multiplicity::Simple;
+            if simple && !self.present.insert(edge) {
+                return;
+            }
+            self.edges.push(edge);
+            self.weights.push(weight);
+            let undirected = self.options.directedness == Directedness::Undirected;
+            if let Some(outgoing) = &mut self.outgoing {
+                outgoing.entry(from).or_default().push(to);
+                if undirected && from != to {
+                    outgoing.entry(to).or_default().push(from);
+                }
             }
         }
     }
 }
 
 mod matrix {
+    use std::collections::HashMap;
+
+    use super::{Directedness, GraphOptions, Multiplicity};
+
+    /// Dense storage is only used once at least `1 / DENSE_RATIO` of the cells are set.
+    const DENSE_RATIO: usize = 16;
 
+    /// Number of parallel edges per cell.
+    #[derive(Debug)]
+    enum Cells {
+        Sparse(HashMap<(usize, usize), u32>),
+        Dense(Vec<Vec<u32>>),
+    }
+
+    impl Default for Cells {
+        fn default() -> Self {
+            Cells::Sparse(HashMap::new())
+        }
+    }
+
+    /// Adjacency matrix which stays a map of cells until it's dense enough to be worth allocating.
     #[derive(Debug, Default)]
     pub struct MatrixGraph {
-        mtx: Vec<Vec<bool>>,
+        size: usize,
+        cells: Cells,
+        options: GraphOptions,
+        /// Weight of every parallel edge in a cell, only for cells that ever got a weight
+        /// other than 1.0.
+        weights: HashMap<(usize, usize), Vec<f32>>,
     }
 
     impl super::Graph for MatrixGraph {
         fn dot_count(&self) -> usize {
-            self.mtx.len()
+            self.size
         }
 
         fn add_edge(&mut self, from: usize, to: usize) {
-            let min_req = from.max(to);
+            self.push_edge(from, to, 1.0)
+        }
+
+        fn add_node(&mut self) -> usize {
+            self.ensure_size(self.size + 1);
+            self.size - 1
+        }
+
+        /// Grows the matrix to the largest referenced node once instead of once per edge.
+        fn add_edges(&mut self, edges: &[(usize, usize)]) {
+            if let Some(required) = edges.iter().map(|&(from, to)| from.max(to) + 1).max() {
+                self.ensure_size(required);
+            }
+            for &(from, to) in edges {
+                self.push_edge(from, to, 1.0)
+            }
+        }
+
+        fn remove_edge(&mut self, from: usize, to: usize) -> bool {
+            let (from, to) = self.options.edge_key(from, to);
+            match &mut self.cells {
+                Cells::Dense(mtx) => match mtx.get_mut(from).and_then(|line| line.get_mut(to)) {
+                    Some(cell) if *cell > 0 => *cell -= 1,
+                    _ => return false,
+                },
+                Cells::Sparse(map) => match map.get_mut(&(from, to)) {
+                    Some(cell) if *cell > 1 => *cell -= 1,
+                    Some(_) => {
+                        map.remove(&(from, to));
+                    }
+                    None => return false,
+                },
+            }
 
-            if min_req >= self.mtx.len() {
-                self.mtx.resize_with(min_req + 1, || vec![false; min_req + 1]);
-                for line in self.mtx.iter_mut() {
-                    line.resize(min_req + 1, false)
+            if let Some(weights) = self.weights.get_mut(&(from, to)) {
+                weights.remove(0);
+                if weights.is_empty() {
+                    self.weights.remove(&(from, to));
                 }
             }
+            true
+        }
+
+        fn remove_node(&mut self, node: usize) {
+            if node >= self.size {
+                return;
+            }
 
-            self.mtx[from][to] = true;
+            let shift = |(from, to): (usize, usize)| {
+                (from - (from > node) as usize, to - (to > node) as usize)
+            };
+            let kept = |(from, to): &(usize, usize)| *from != node && *to != node;
+            let cells = self.take_cells()
+                .into_iter()
+                .filter(|(cell, _)| kept(cell))
+                .map(|(cell, count)| (shift(cell), count))
+                .collect();
+            self.weights = std::mem::take(&mut self.weights)
+                .into_iter()
+                .filter(|(cell, _)| kept(cell))
+                .map(|(cell, weights)| (shift(cell), weights))
+                .collect();
+            self.size -= 1;
+            self.cells = pack(cells, self.size);
         }
 
         fn for_each_edge(&self, cb: &mut dyn FnMut(usize, usize)) {
-            (0..self.mtx.len())
-                .flat_map(|from| (from..self.mtx.len()).map(move |to| (from, to)))
-                .filter(|(from, to)| self.mtx[*from][*to])
-                .for_each(on_tied(cb))
+            self.for_each_cell(&mut |(from, to), count| (0..count).for_each(|_| cb(from, to)));
+        }
+
+        fn directedness(&self) -> Directedness {
+            self.options.directedness
+        }
+
+        fn neighbors(&self, node: usize, cb: &mut dyn FnMut(usize)) {
+            if node >= self.size {
+                return;
+            }
+            for to in 0..self.size {
+                (0..self.cell(node, to)).for_each(|_| cb(to));
+            }
+        }
+
+        fn has_edge(&self, from: usize, to: usize) -> bool {
+            self.cell(from, to) > 0
+        }
+
+        fn out_degree(&self, node: usize) -> usize {
+            if node >= self.size {
+                return 0;
+            }
+            let row: usize = (0..self.size).map(|to| self.cell(node, to) as usize).sum();
+            match self.options.directedness {
+                Directedness::Directed => row,
+                // The diagonal cell is the only one that stands for both ends of its edges.
+                Directedness::Undirected => row + self.cell(node, node) as usize,
+            }
+        }
+
+        fn edges_between(&self, from: usize, to: usize) -> Vec<(usize, usize)> {
+            vec![(from, to); self.cell(from, to) as usize]
+        }
+
+        fn edge_multiplicity(&self, from: usize, to: usize) -> usize {
+            self.cell(from, to) as usize
+        }
+
+        fn assert_valid(&self) {
+            if cfg!(debug_assertions) {
+                let len = self.size;
+                let simple = self.options.multiplicity == Multiplicity::Simple;
+                let undirected = self.options.directedness == Directedness::Undirected;
+                match &self.cells {
+                    Cells::Dense(mtx) => {
+                        assert_eq!(mtx.len(), len, "matrix is not {len} high");
+                        for (idx, line) in mtx.iter().enumerate() {
+                            assert_eq!(line.len(), len, "matrix row {idx} is not {len} wide");
+                            assert!(
+                                !simple || line.iter().all(|count| *count <= 1),
+                                "matrix row {idx} has parallel edges"
+                            );
+                            assert!(
+                                !undirected || line[..idx].iter().all(|count| *count == 0),
+                                "undirected matrix row {idx} has cells below the diagonal"
+                            );
+                        }
+                    }
+                    Cells::Sparse(map) => {
+                        for ((from, to), count) in map.iter() {
+                            assert!(
+                                *from < len && *to < len,
+                                "cell ({from}, {to}) is out of {len}x{len}"
+                            );
+                            assert!(
+                                !simple || *count <= 1,
+                                "cell ({from}, {to}) has parallel edges"
+                            );
+                            assert!(
+                                !undirected || from <= to,
+                                "undirected cell ({from}, {to}) is below the diagonal"
+                            );
+                        }
+                    }
+                }
+            }
         }
     }
 
-    impl super::DrawableGraph for MatrixGraph {}
+    impl super::WeightedGraph for MatrixGraph {
+        fn add_weighted_edge(&mut self, from: usize, to: usize, weight: f32) {
+            self.push_edge(from, to, weight)
+        }
+
+        fn for_each_weighted_edge(&self, cb: &mut dyn FnMut(usize, usize, f32)) {
+            self.for_each_cell(&mut |(from, to), count| match self.weights.get(&(from, to)) {
+                Some(weights) => weights.iter().for_each(|weight| cb(from, to, *weight)),
+                None => (0..count).for_each(|_| cb(from, to, 1.0)),
+            });
+        }
+    }
+
+    impl super::DrawableGraph for MatrixGraph {
+        fn as_graph(&self) -> &dyn super::Graph {
+            self
+        }
+
+        fn as_weighted(&self) -> &dyn super::WeightedGraph {
+            self
+        }
+    }
 
     impl MatrixGraph {
-        pub fn with_dots_count(count: usize) -> Self {
+        pub fn with_options(count: usize, options: GraphOptions) -> Self {
             Self {
-                mtx: vec![vec![false; count]; count],
+                size: count,
+                cells: Cells::default(),
+                options,
+                weights: HashMap::new(),
+            }
+        }
+
+        fn push_edge(&mut self, from: usize, to: usize, weight: f32) {
+            self.ensure_size(from.max(to) + 1);
+            let (from, to) = self.options.edge_key(from, to);
+            let before = self.cell(from, to);
+            let multiplicity = self.options.multiplicity;
+            let add = |cell: &mut u32| match multiplicity {
+                Multiplicity::Simple => *cell = 1,
+                Multiplicity::Multi => *cell += 1,
+            };
+
+            match &mut self.cells {
+                Cells::Dense(mtx) => add(&mut mtx[from][to]),
+                Cells::Sparse(map) if !is_dense(map.len() + 1, self.size) => {
+                    add(map.entry((from, to)).or_default())
+                }
+                _ => {
+                    let mut map = self.take_cells();
+                    add(map.entry((from, to)).or_default());
+                    self.cells = pack(map, self.size);
+                }
+            }
+
+            let added = self.cell(from, to) > before;
+            if added && (weight != 1.0 || self.weights.contains_key(&(from, to))) {
+                self.weights
+                    .entry((from, to))
+                    .or_insert_with(|| vec![1.0; before as usize])
+                    .push(weight);
             }
         }
+
+        /// Grows the matrix to at least `size` nodes.
+        ///
+        /// Dense cells that stay dense enough are grown in place, existing rows are extended and
+        /// new rows pushed at full width. Otherwise the cells move to sparse storage.
+        fn ensure_size(&mut self, size: usize) {
+            if size <= self.size {
+                return;
+            }
+            self.size = size;
+            match &mut self.cells {
+                Cells::Sparse(_) => {}
+                Cells::Dense(mtx) => {
+                    let set_cells = mtx.iter().flatten().filter(|count| **count > 0).count();
+                    if is_dense(set_cells, size) {
+                        mtx.iter_mut().for_each(|line| line.resize(size, 0));
+                        mtx.resize_with(size, || vec![0; size]);
+                        debug_assert!(mtx.iter().all(|line| line.len() == size));
+                    } else {
+                        let map = self.take_cells();
+                        self.cells = Cells::Sparse(map);
+                    }
+                }
+            }
+        }
+
+        /// Calls `cb` with every non-empty cell and its edge count, row by row.
+        fn for_each_cell(&self, cb: &mut dyn FnMut((usize, usize), u32)) {
+            match &self.cells {
+                Cells::Dense(mtx) => (0..mtx.len())
+                    .flat_map(|from| (0..mtx.len()).map(move |to| (from, to)))
+                    .filter(|&(from, to)| mtx[from][to] > 0)
+                    .for_each(|(from, to)| cb((from, to), mtx[from][to])),
+                Cells::Sparse(map) => {
+                    let mut cells: Vec<_> = map
+                        .iter()
+                        .map(|(cell, count)| (*cell, *count))
+                        .collect();
+                    cells.sort_unstable();
+                    cells.into_iter().for_each(|(cell, count)| cb(cell, count))
+                }
+            }
+        }
+
+        /// Edges between `from` and `to`, in either direction for undirected graphs.
+        fn cell(&self, from: usize, to: usize) -> u32 {
+            let (from, to) = self.options.edge_key(from, to);
+            match &self.cells {
+                Cells::Sparse(map) => map.get(&(from, to)).copied().unwrap_or(0),
+                Cells::Dense(mtx) => mtx
+                    .get(from)
+                    .and_then(|line| line.get(to))
+                    .copied()
+                    .unwrap_or(0),
+            }
+        }
+
+        fn take_cells(&mut self) -> HashMap<(usize, usize), u32> {
+            match std::mem::take(&mut self.cells) {
+                Cells::Sparse(map) => map,
+                Cells::Dense(mtx) => mtx
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(from, line)| line.iter()
+                        .enumerate()
+                        .filter(|(_, count)| **count > 0)
+                        .map(move |(to, count)| ((from, to), *count)))
+                    .collect(),
+            }
+        }
+    }
+
+    fn is_dense(set_cells: usize, size: usize) -> bool {
+        set_cells.saturating_mul(DENSE_RATIO) >= size.saturating_mul(size)
+    }
+
+    fn pack(map: HashMap<(usize, usize), u32>, size: usize) -> Cells {
+        if !is_dense(map.len(), size) {
+            return Cells::Sparse(map);
+        }
+
+        let mut mtx = vec![vec![0; size]; size];
+        for ((from, to), count) in map {
+            mtx[from][to] = count;
+        }
+        Cells::Dense(mtx)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::graph::Graph;
+
+        #[test]
+        fn far_edge_stays_sparse() {
+            let mut graph = MatrixGraph::default();
+            graph.add_edge(0, 100_000);
+
+            assert_eq!(graph.dot_count(), 100_001);
+            assert!(matches!(graph.cells, Cells::Sparse(_)));
+            assert!(graph.has_edge(0, 100_000));
+        }
+
+        #[test]
+        fn large_initial_count_stays_sparse() {
+            let mut graph = MatrixGraph::with_options(100_000, GraphOptions::default());
+            for (from, to) in [(0, 1), (1, 2), (99_999, 0)] {
+                graph.add_edge(from, to);
+            }
+
+            assert_eq!(graph.dot_count(), 100_000);
+            assert!(matches!(&graph.cells, Cells::Sparse(map) if map.len() == 3));
+            assert!(graph.has_edge(99_999, 0));
+        }
+
+        #[test]
+        fn batch_is_sized_to_largest_index() {
+            let edges = [(0, 1), (6, 2), (3, 3), (2, 4)];
+            for order in [edges, [edges[1], edges[3], edges[0], edges[2]]] {
+                let mut graph = MatrixGraph::default();
+                graph.add_edges(&order);
+                graph.assert_valid();
+
+                assert_eq!(graph.dot_count(), 7, "{order:?}");
+                assert!(order.iter().all(|&(from, to)| graph.has_edge(from, to)), "{order:?}");
+            }
+        }
+
+        #[test]
+        fn growing_dense_matrix_stays_square() {
+            let increasing: Vec<_> = (0..6).map(|node| (node, node)).collect();
+            let decreasing: Vec<_> = increasing.iter().rev().copied().collect();
+            let interleaved = vec![(2, 0), (0, 5), (3, 1), (1, 1), (4, 2), (0, 0)];
+            for edges in [increasing, decreasing, interleaved] {
+                let mut graph = MatrixGraph::default();
+                for &(from, to) in edges.iter() {
+                    graph.add_edge(from, to);
+                    graph.assert_valid();
+                }
+
+                assert!(matches!(graph.cells, Cells::Dense(_)), "{edges:?}");
+                assert_eq!(graph.dot_count(), 6, "{edges:?}");
+                assert!(edges.iter().all(|&(from, to)| graph.has_edge(from, to)), "{edges:?}");
+
+                graph.add_edge(5, 1_000);
+                assert!(matches!(graph.cells, Cells::Sparse(_)), "{edges:?}");
+                assert!(edges.iter().all(|&(from, to)| graph.has_edge(from, to)), "{edges:?}");
+            }
+        }
+
+        #[test]
+        #[cfg(debug_assertions)]
+        #[should_panic(expected = "matrix row 1 is not 2 wide")]
+        fn non_square_matrix_is_caught() {
+            let mut graph = MatrixGraph::with_options(2, GraphOptions::default());
+            graph.cells = Cells::Dense(vec![vec![0, 1], vec![0]]);
+            graph.assert_valid();
+        }
+    }
+}
+
+mod adjacency {
+    use super::{Directedness, GraphOptions, Multiplicity};
+
+    /// Neighbors of every node along with edge weights, in the order edges were added.
+    /// Undirected edges are listed at both ends.
+    #[derive(Debug, Default)]
+    pub struct AdjacencyListGraph {
+        adjacent: Vec<Vec<(usize, f32)>>,
+        options: GraphOptions,
+    }
+
+    impl super::Graph for AdjacencyListGraph {
+        fn dot_count(&self) -> usize {
+            self.adjacent.len()
+        }
+
+        fn for_each_edge(&self, cb: &mut dyn FnMut(usize, usize)) {
+            self.for_each_stored(&mut |from, to, _| cb(from, to))
+        }
+
+        fn add_edge(&mut self, from: usize, to: usize) {
+            self.push_edge(from, to, 1.0)
+        }
+
+        fn add_node(&mut self) -> usize {
+            self.adjacent.push(vec![]);
+            self.adjacent.len() - 1
+        }
+
+        fn remove_edge(&mut self, from: usize, to: usize) -> bool {
+            if !self.unlink(from, to) {
+                return false;
+            }
+            if self.undirected() && from != to {
+                self.unlink(to, from);
+            }
+            true
+        }
+
+        fn remove_node(&mut self, node: usize) {
+            if node >= self.adjacent.len() {
+                return;
+            }
+
+            self.adjacent.remove(node);
+            for next in self.adjacent.iter_mut() {
+                next.retain(|(to, _)| *to != node);
+                for (to, _) in next.iter_mut() {
+                    *to -= (*to > node) as usize;
+                }
+            }
+        }
+
+        fn directedness(&self) -> Directedness {
+            self.options.directedness
+        }
+
+        fn has_edge(&self, from: usize, to: usize) -> bool {
+            self.adjacent
+                .get(from)
+                .is_some_and(|next| next.iter().any(|(next, _)| *next == to))
+        }
+
+        fn neighbors(&self, node: usize, cb: &mut dyn FnMut(usize)) {
+            self.adjacent.get(node)
+                .into_iter()
+                .flatten()
+                .for_each(|(to, _)| cb(*to))
+        }
+
+        fn edges_between(&self, from: usize, to: usize) -> Vec<(usize, usize)> {
+            self.adjacent.get(from)
+                .into_iter()
+                .flatten()
+                .filter(|(next, _)| *next == to)
+                .map(|_| (from, to))
+                .collect()
+        }
+
+        fn assert_valid(&self) {
+            if cfg!(debug_assertions) {
+                let len = self.adjacent.len();
+                let simple = self.options.multiplicity == Multiplicity::Simple;
+                for (from, next) in self.adjacent.iter().enumerate() {
+                    for (idx, (to, _)) in next.iter().enumerate() {
+                        assert!(*to < len, "edge target {to} is not a known dot");
+                        assert!(
+                            !simple || next[..idx].iter().all(|(other, _)| other != to),
+                            "node {from} has parallel edges to {to}"
+                        );
+                        assert!(
+                            !self.undirected()
+                                || self.edges_between(from, *to).len()
+                                    == self.edges_between(*to, from).len(),
+                            "undirected edge ({from}, {to}) is only listed at one end"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    impl super::WeightedGraph for AdjacencyListGraph {
+        fn add_weighted_edge(&mut self, from: usize, to: usize, weight: f32) {
+            self.push_edge(from, to, weight)
+        }
+
+        fn for_each_weighted_edge(&self, cb: &mut dyn FnMut(usize, usize, f32)) {
+            self.for_each_stored(cb)
+        }
+    }
+
+    impl super::DrawableGraph for AdjacencyListGraph {
+        fn as_graph(&self) -> &dyn super::Graph {
+            self
+        }
+
+        fn as_weighted(&self) -> &dyn super::WeightedGraph {
+            self
+        }
+    }
+
+    impl AdjacencyListGraph {
+        pub fn with_options(count: usize, options: GraphOptions) -> Self {
+            Self {
+                adjacent: vec![vec![]; count],
+                options,
+            }
+        }
+
+        fn undirected(&self) -> bool {
+            self.options.directedness == Directedness::Undirected
+        }
+
+        fn push_edge(&mut self, from: usize, to: usize, weight: f32) {
+            let required = from.max(to) + 1;
+            if self.adjacent.len() < required {
+                self.adjacent.resize_with(required, Vec::new);
+            }
+
+            let simple = self.options.multiplicity == Multiplicity::Simple;
+            if simple && super::Graph::has_edge(self, from, to) {
+                return;
+            }
+            self.adjacent[from].push((to, weight));
+            if self.undirected() && from != to {
+                self.adjacent[to].push((from, weight));
+            }
+        }
+
+        /// Removes the earliest edge from `from` to `to` out of the list of `from`.
+        fn unlink(&mut self, from: usize, to: usize) -> bool {
+            let Some(next) = self.adjacent.get_mut(from) else {
+                return false;
+            };
+            let Some(idx) = next.iter().position(|(next, _)| *next == to) else {
+                return false;
+            };
+            next.remove(idx);
+            true
+        }
+
+        /// Every edge once, undirected ones from their smaller end.
+        fn for_each_stored(&self, cb: &mut dyn FnMut(usize, usize, f32)) {
+            let undirected = self.undirected();
+            for (from, next) in self.adjacent.iter().enumerate() {
+                for &(to, weight) in next.iter() {
+                    if !undirected || from <= to {
+                        cb(from, to, weight)
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::ValueEnum;
+
+    use super::*;
+    use crate::{
+        gui::{Coord, RenderConfig},
+        layout::Layout,
+    };
+
+    fn undirected(backend: GraphBackend, count: usize) -> Box<dyn DrawableGraph> {
+        let options = GraphOptions { directedness: Directedness::Undirected, ..Default::default() };
+        with_options(backend, count, options)
+    }
+
+    /// Every edge as reported by `for_each_edge`, in its order.
+    fn edges(graph: &dyn Graph) -> Vec<(usize, usize)> {
+        let mut edges = vec![];
+        graph.for_each_edge(&mut |from, to| edges.push((from, to)));
+        edges
+    }
+
+    /// The graph drawn by `main.rs`.
+    fn sample(backend: GraphBackend) -> Box<dyn DrawableGraph> {
+        let mut graph = with_dots_count(backend, 10);
+        for (from, to) in [(0, 1), (1, 2), (2, 0), (0, 4)] {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+
+    #[test]
+    fn component_of_sample_node() {
+        let graph = sample(GraphBackend::EdgeList);
+
+        assert_eq!(graph.component_of(0), vec![0, 1, 2, 4]);
+        assert_eq!(graph.component_of(4), vec![0, 1, 2, 4]);
+        assert_eq!(graph.component_of(7), vec![7]);
+    }
+
+    #[test]
+    fn sample_reaches_its_own_component_only() {
+        let graph = sample(GraphBackend::EdgeList);
+
+        assert!(graph.path_exists(0, 2));
+        assert!(graph.path_exists(2, 4));
+        assert!(!graph.path_exists(0, 3));
+        assert!(!graph.path_exists(4, 0));
+        assert!(graph.path_exists(3, 3));
+        assert!(!graph.path_exists(0, 10));
+    }
+
+    #[test]
+    fn adjacency_list_of_sample() {
+        let adjacency = sample(GraphBackend::EdgeList).adjacency_list();
+
+        assert_eq!(adjacency.len(), 10);
+        assert_eq!(adjacency[0], vec![1, 4]);
+        assert_eq!(adjacency[2], vec![0]);
+        assert!(adjacency[3].is_empty() && adjacency[4].is_empty());
+    }
+
+    #[test]
+    fn simple_graphs_keep_one_copy_of_an_edge() {
+        let options = GraphOptions { multiplicity: Multiplicity::Simple, ..Default::default() };
+        for &backend in GraphBackend::value_variants() {
+            let mut graph = with_options(backend, 2, options);
+            graph.add_edge(0, 1);
+            graph.add_edge(0, 1);
+
+            assert_eq!(edges(graph.as_graph()), vec![(0, 1)], "{backend:?}");
+            assert_eq!(graph.edge_multiplicity(0, 1), 1, "{backend:?}");
+        }
+    }
+
+    #[test]
+    fn parallel_edges_are_all_found() {
+        let options = GraphOptions { multiplicity: Multiplicity::Multi, ..Default::default() };
+        let mut graph = with_options(GraphBackend::EdgeList, 2, options);
+        graph.add_edge(0, 1);
+        graph.add_weighted_edge(0, 1, 2.0);
+        graph.add_weighted_edge(0, 1, 3.0);
+        graph.add_edge(1, 0);
+
+        assert_eq!(graph.edges_between(0, 1), vec![(0, 1); 3]);
+        assert_eq!(graph.weights_between(0, 1), vec![1.0, 2.0, 3.0]);
+        assert_eq!(graph.edges_between(1, 0), vec![(1, 0)]);
+    }
+
+    #[test]
+    fn neighbor_index_answers_like_a_scan() {
+        for directedness in [Directedness::Directed, Directedness::Undirected] {
+            let plain = GraphOptions {
+                multiplicity: Multiplicity::Multi,
+                directedness,
+                ..Default::default()
+            };
+            let indexed = GraphOptions { neighbor_index: true, ..plain };
+            let mut graphs = [plain, indexed]
+                .map(|options| with_options(GraphBackend::EdgeList, 5, options));
+            for graph in graphs.iter_mut() {
+                for (from, to) in [(0, 1), (1, 2), (2, 0), (0, 4), (0, 1), (3, 3), (4, 2)] {
+                    graph.add_edge(from, to);
+                }
+                assert!(graph.remove_edge(0, 1));
+                assert!(graph.remove_edge(2, 0));
+                assert!(!graph.remove_edge(3, 4));
+                graph.add_edge(3, 1);
+                graph.remove_node(2);
+                graph.add_edge(2, 0);
+                graph.assert_valid();
+            }
+
+            let [plain, indexed] = &graphs;
+            assert_eq!(plain.adjacency_list(), indexed.adjacency_list(), "{directedness:?}");
+            for node in 0..plain.dot_count() {
+                let neighbors = |graph: &dyn Graph| {
+                    let mut neighbors = vec![];
+                    graph.neighbors(node, &mut |next| neighbors.push(next));
+                    neighbors.sort_unstable();
+                    neighbors
+                };
+                assert_eq!(
+                    neighbors(plain.as_graph()),
+                    neighbors(indexed.as_graph()),
+                    "{directedness:?} {node}"
+                );
+                assert_eq!(plain.degree(node), indexed.degree(node), "{directedness:?} {node}");
+                assert_eq!(
+                    plain.out_degree(node),
+                    indexed.out_degree(node),
+                    "{directedness:?} {node}"
+                );
+                for to in 0..plain.dot_count() {
+                    assert_eq!(
+                        plain.has_edge(node, to),
+                        indexed.has_edge(node, to),
+                        "{directedness:?} {node} {to}"
+                    );
+                    assert_eq!(
+                        plain.edges_between(node, to),
+                        indexed.edges_between(node, to),
+                        "{directedness:?} {node} {to}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gaps_in_node_indices_are_counted() {
+        for &backend in GraphBackend::value_variants() {
+            let mut graph = with_dots_count(backend, 0);
+            assert_eq!((graph.dot_count(), graph.max_node_index()), (0, None), "{backend:?}");
+
+            graph.add_edge(0, 1);
+            graph.add_edge(3, 7);
+            assert_eq!(graph.dot_count(), 8, "{backend:?}");
+            assert_eq!(graph.max_node_index(), Some(7), "{backend:?}");
+
+            let graph = with_dots_count(backend, 12);
+            assert_eq!((graph.dot_count(), graph.max_node_index()), (12, None), "{backend:?}");
+        }
+    }
+
+    #[test]
+    fn backends_report_the_same_edges() {
+        for &backend in GraphBackend::value_variants() {
+            let mut graph = with_dots_count(backend, 5);
+            for (from, to) in [(0, 1), (3, 0), (2, 1), (1, 3), (4, 4), (4, 2)] {
+                graph.add_edge(from, to);
+            }
+            let mut edges = edges(graph.as_graph());
+            edges.sort_unstable();
+
+            assert_eq!(edges, vec![(0, 1), (1, 3), (2, 1), (3, 0), (4, 2), (4, 4)], "{backend:?}");
+        }
+    }
+
+    #[test]
+    fn removed_edge_is_the_only_one_gone() {
+        for &backend in GraphBackend::value_variants() {
+            let mut graph = with_dots_count(backend, 3);
+            for (from, to) in [(0, 1), (1, 2), (2, 0)] {
+                graph.add_edge(from, to);
+            }
+
+            assert!(graph.remove_edge(1, 2), "{backend:?}");
+            assert!(!graph.remove_edge(1, 2), "{backend:?}");
+            assert!(!graph.remove_edge(1, 0), "{backend:?}");
+            assert_eq!(edges(graph.as_graph()), vec![(0, 1), (2, 0)], "{backend:?}");
+
+            let options = GraphOptions { multiplicity: Multiplicity::Multi, ..Default::default() };
+            let mut graph = with_options(backend, 2, options);
+            graph.add_edge(0, 1);
+            graph.add_edge(0, 1);
+            assert!(graph.remove_edge(0, 1), "{backend:?}");
+            assert_eq!(edges(graph.as_graph()), vec![(0, 1)], "{backend:?}");
+        }
+    }
+
+    #[test]
+    fn neighbors_of_sample_nodes() {
+        for &backend in GraphBackend::value_variants() {
+            let graph = sample(backend);
+            let neighbors = |node| {
+                let mut neighbors = vec![];
+                graph.neighbors(node, &mut |next| neighbors.push(next));
+                neighbors.sort_unstable();
+                neighbors
+            };
+
+            assert_eq!(neighbors(0), vec![1, 4], "{backend:?}");
+            assert_eq!(neighbors(1), vec![2], "{backend:?}");
+            assert_eq!(neighbors(2), vec![0], "{backend:?}");
+            assert!(neighbors(4).is_empty() && neighbors(9).is_empty(), "{backend:?}");
+        }
+    }
+
+    #[test]
+    fn star_center_touches_every_other_node() {
+        for &backend in GraphBackend::value_variants() {
+            let mut graph = with_dots_count(backend, 6);
+            for (from, to) in [(0, 1), (0, 2), (3, 0), (0, 4), (5, 0)] {
+                graph.add_edge(from, to);
+            }
+
+            assert_eq!(graph.degree(0), 5, "{backend:?}");
+            assert_eq!((graph.out_degree(0), graph.in_degree(0)), (3, 2), "{backend:?}");
+            assert!((1..6).all(|leaf| graph.degree(leaf) == 1), "{backend:?}");
+
+            graph.add_edge(0, 0);
+            assert_eq!(graph.degree(0), 7, "{backend:?}");
+            assert_eq!((graph.out_degree(0), graph.in_degree(0)), (4, 3), "{backend:?}");
+        }
+    }
+
+    #[test]
+    fn removed_node_renumbers_later_ones() {
+        for &backend in GraphBackend::value_variants() {
+            let mut graph = sample(backend);
+            graph.remove_node(1);
+            graph.assert_valid();
+
+            assert_eq!(graph.dot_count(), 9, "{backend:?}");
+            let mut remaining = edges(graph.as_graph());
+            remaining.sort_unstable();
+            assert_eq!(remaining, vec![(0, 3), (1, 0)], "{backend:?}");
+        }
+    }
+
+    #[test]
+    fn multiplicity_is_counted_alike_on_every_backend() {
+        for multiplicity in [Multiplicity::Simple, Multiplicity::Multi] {
+            let options = GraphOptions { multiplicity, ..Default::default() };
+            let copies = if multiplicity == Multiplicity::Multi { 3 } else { 1 };
+            for &backend in GraphBackend::value_variants() {
+                let mut graph = with_options(backend, 3, options);
+                graph.add_edges(&[(0, 1), (0, 1), (1, 2), (0, 1)]);
+
+                assert_eq!(graph.edge_multiplicity(0, 1), copies, "{backend:?} {multiplicity:?}");
+                assert_eq!(graph.edge_multiplicity(1, 2), 1, "{backend:?} {multiplicity:?}");
+                assert_eq!(graph.edge_multiplicity(1, 0), 0, "{backend:?} {multiplicity:?}");
+                let edge_count = edges(graph.as_graph()).len();
+                assert_eq!(edge_count, copies + 1, "{backend:?} {multiplicity:?}");
+                assert_eq!(graph.degree(1), copies + 1, "{backend:?} {multiplicity:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn conversion_round_trip_keeps_edges() {
+        let options = GraphOptions { multiplicity: Multiplicity::Multi, ..Default::default() };
+        let mut graph = with_options(GraphBackend::EdgeList, 10, options);
+        graph.add_edges(&[(0, 1), (1, 2), (2, 0), (0, 4), (4, 0), (3, 3), (0, 1)]);
+
+        let matrix = convert(graph.as_graph(), GraphBackend::Matrix);
+        let back = convert(matrix.as_graph(), GraphBackend::EdgeList);
+
+        let sorted = |graph: &dyn Graph| {
+            let mut edges = edges(graph);
+            edges.sort_unstable();
+            edges
+        };
+        assert_eq!((matrix.dot_count(), back.dot_count()), (10, 10));
+        assert_eq!(sorted(matrix.as_graph()), sorted(graph.as_graph()));
+        assert_eq!(sorted(back.as_graph()), sorted(graph.as_graph()));
+    }
+
+    #[test]
+    fn undirected_edges_are_followed_both_ways() {
+        for &backend in GraphBackend::value_variants() {
+            let mut graph = undirected(backend, 3);
+            graph.add_edge(2, 0);
+
+            assert!(graph.path_exists(2, 0), "{backend:?}");
+            assert!(graph.path_exists(0, 2), "{backend:?}");
+            assert_eq!(graph.adjacency_list(), vec![vec![2], vec![], vec![0]], "{backend:?}");
+            assert_eq!(graph.weights_between(2, 0), vec![1.0], "{backend:?}");
+        }
+    }
+
+    #[test]
+    fn layout_is_fitted_like_drawing() {
+        let graph = with_dots_count(GraphBackend::EdgeList, 2);
+        let coords = vec![Coord::new(0.2, 0.2), Coord::new(0.4, 0.6)];
+        let config = RenderConfig::default()
+            .layout(Layout::Fixed(coords.clone()))
+            .fit_margin(Some(0.1));
+
+        assert_eq!(graph.layout(&config), crate::layout::fit_to_view(&coords, 0.1));
+        assert_eq!(graph.layout(&config.fit_margin(None)), coords);
     }
 
-    fn on_tied<Fst, Sec, F: FnMut(Fst, Sec)>(mut f: F) -> impl FnMut((Fst, Sec)) {
-        move |(fst, sec)| f(fst, sec)
+    #[test]
+    fn directed_edges_are_followed_forward_only() {
+        for &backend in GraphBackend::value_variants() {
+            let mut graph = with_dots_count(backend, 3);
+            graph.add_edge(2, 0);
+
+            assert!(graph.path_exists(2, 0), "{backend:?}");
+            assert!(!graph.path_exists(0, 2), "{backend:?}");
+            assert_eq!(graph.adjacency_list(), vec![vec![], vec![], vec![0]], "{backend:?}");
+        }
+    }
+
+    /// Graph with nothing but the required methods, to check the provided ones.
+    #[derive(Debug)]
+    struct BareUndirected(Vec<(usize, usize)>);
+
+    impl Graph for BareUndirected {
+        fn dot_count(&self) -> usize {
+            3
+        }
+
+        fn for_each_edge(&self, cb: &mut dyn FnMut(usize, usize)) {
+            self.0.iter().for_each(|&(from, to)| cb(from, to))
+        }
+
+        fn add_edge(&mut self, from: usize, to: usize) {
+            self.0.push((from.min(to), from.max(to)))
+        }
+
+        fn add_node(&mut self) -> usize {
+            unimplemented!()
+        }
+
+        fn remove_edge(&mut self, _: usize, _: usize) -> bool {
+            unimplemented!()
+        }
+
+        fn remove_node(&mut self, _: usize) {
+            unimplemented!()
+        }
+
+        fn assert_valid(&self) {}
+
+        fn directedness(&self) -> Directedness {
+            Directedness::Undirected
+        }
+    }
+
+    #[test]
+    fn provided_queries_follow_undirected_edges_both_ways() {
+        let mut graph = BareUndirected(vec![]);
+        graph.add_edges(&[(1, 0), (2, 2)]);
+        let neighbors = |node| {
+            let mut neighbors = vec![];
+            graph.neighbors(node, &mut |next| neighbors.push(next));
+            neighbors
+        };
+
+        assert!(graph.has_edge(0, 1) && graph.has_edge(1, 0));
+        assert!(!graph.has_edge(0, 2));
+        assert_eq!((neighbors(0), neighbors(1), neighbors(2)), (vec![1], vec![0], vec![2]));
     }
 }
@@ -0,0 +1,943 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+};
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use super::{
+    Directedness, DrawableGraph, Graph, GraphBackend, GraphOptions, Multiplicity, WeightedGraph,
+};
+
+/// Pairs of `subset` nodes that aren't connected in either direction,
+/// i.e. the edges missing for `subset` to become a clique.
+///
+/// Pairs follow `subset` order, self-loops are never reported.
+pub fn complement_within(graph: &dyn Graph, subset: &[usize]) -> Vec<(usize, usize)> {
+    let members: HashSet<_> = subset.iter().copied().collect();
+    let mut present = HashSet::new();
+    graph.for_each_edge(&mut |from, to| {
+        if members.contains(&from) && members.contains(&to) {
+            present.insert((from.min(to), from.max(to)));
+        }
+    });
+
+    let mut seen = HashSet::new();
+    let unique: Vec<_> = subset
+        .iter()
+        .copied()
+        .filter(|node| seen.insert(*node))
+        .collect();
+
+    unique
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, &from)| unique[idx + 1..].iter().map(move |&to| (from, to)))
+        .filter(|&(from, to)| !present.contains(&(from.min(to), from.max(to))))
+        .collect()
+}
+
+/// Whether the edge set looks directed, i.e. some edge `a -> b` has no matching `b -> a`.
+///
+/// Graphs without edges and graphs where every edge has its reverse are considered undirected.
+pub fn infer_directedness(graph: &dyn Graph) -> bool {
+    let mut edges = HashSet::new();
+    graph.for_each_edge(&mut |from, to| {
+        edges.insert((from, to));
+    });
+
+    edges.iter().any(|&(from, to)| !edges.contains(&(to, from)))
+}
+
+/// Largest node count `maximal_cliques` works on, node sets are kept as `u128` bitsets.
+pub const MAX_CLIQUE_NODES: usize = u128::BITS as usize;
+
+/// All maximal cliques of the graph with edges taken as undirected, via Bron–Kerbosch with pivoting.
+///
+/// Every clique is sorted, cliques are ordered lexicographically. Isolated nodes are cliques
+/// of their own and self-loops are ignored. `None` for graphs over `MAX_CLIQUE_NODES` nodes.
+pub fn maximal_cliques(graph: &dyn Graph) -> Option<Vec<Vec<usize>>> {
+    let count = node_bound(graph);
+    if count > MAX_CLIQUE_NODES {
+        return None;
+    }
+
+    let mut adjacent = vec![0u128; count];
+    graph.for_each_edge(&mut |from, to| {
+        if from != to {
+            adjacent[from] |= 1 << to;
+            adjacent[to] |= 1 << from;
+        }
+    });
+
+    let all = (0..count).fold(0u128, |set, node| set | 1 << node);
+
+    let mut cliques = vec![];
+    bron_kerbosch(&adjacent, 0, all, 0, &mut cliques);
+    cliques.sort_unstable();
+    Some(cliques)
+}
+
+fn bron_kerbosch(
+    adjacent: &[u128],
+    clique: u128,
+    mut candidates: u128,
+    mut excluded: u128,
+    out: &mut Vec<Vec<usize>>,
+) {
+    if candidates == 0 {
+        if excluded == 0 {
+            out.push(bits(clique).collect());
+        }
+        return;
+    }
+
+    let pivot = bits(candidates | excluded)
+        .max_by_key(|&node| (candidates & adjacent[node]).count_ones())
+        .expect("candidates are not empty");
+
+    for node in bits(candidates & !adjacent[pivot]) {
+        let bit = 1 << node;
+        bron_kerbosch(
+            adjacent,
+            clique | bit,
+            candidates & adjacent[node],
+            excluded & adjacent[node],
+            out,
+        );
+        candidates &= !bit;
+        excluded |= bit;
+    }
+}
+
+fn bits(mut set: u128) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        (set != 0).then(|| {
+            let idx = set.trailing_zeros() as usize;
+            set &= set - 1;
+            idx
+        })
+    })
+}
+
+/// One past the largest node index, whether it comes from `dot_count` or from an edge.
+fn node_bound(graph: &dyn Graph) -> usize {
+    let mut bound = graph.dot_count();
+    graph.for_each_edge(&mut |from, to| bound = bound.max(from + 1).max(to + 1));
+    bound
+}
+
+/// Pearson correlation between degrees at both ends of every edge, with edges taken as undirected.
+///
+/// Positive values mean hubs tend to connect to hubs, negative ones that they connect to
+/// low-degree nodes. Gives `NaN` when there are no edges or every edge end has the same degree,
+/// the correlation is undefined then.
+pub fn degree_assortativity(graph: &dyn Graph) -> f64 {
+    let mut degrees: HashMap<usize, usize> = HashMap::new();
+    let mut edges = vec![];
+    graph.for_each_edge(&mut |from, to| {
+        *degrees.entry(from).or_default() += 1;
+        *degrees.entry(to).or_default() += 1;
+        edges.push((from, to));
+    });
+
+    let ends: Vec<_> = edges
+        .iter()
+        .flat_map(|&(from, to)| {
+            let from = degrees[&from] as f64;
+            let to = degrees[&to] as f64;
+            [(from, to), (to, from)]
+        })
+        .collect();
+    if ends.is_empty() {
+        return f64::NAN;
+    }
+
+    let len = ends.len() as f64;
+    let mean = ends.iter().map(|(from, _)| from).sum::<f64>() / len;
+    let covariance = ends
+        .iter()
+        .map(|(from, to)| (from - mean) * (to - mean))
+        .sum::<f64>();
+    let variance = ends
+        .iter()
+        .map(|(from, _)| (from - mean).powi(2))
+        .sum::<f64>();
+
+    covariance / variance
+}
+
+/// Visited nodes of a walk from `start` making up to `steps` moves,
+/// each to an out-neighbor picked uniformly with an RNG seeded by `seed`.
+///
+/// Undirected edges can be walked either way. Parallel edges make their target
+/// proportionally more likely. The walk stops early
+/// at a node without outgoing edges, a `start` outside of the graph gives an empty walk.
+pub fn random_walk(graph: &dyn Graph, start: usize, steps: usize, seed: u64) -> Vec<usize> {
+    if start >= graph.dot_count() {
+        return vec![];
+    }
+
+    let mut outgoing: HashMap<usize, Vec<usize>> = HashMap::new();
+    graph.for_each_arc(&mut |from, to| outgoing.entry(from).or_default().push(to));
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut walk = vec![start];
+    let mut current = start;
+    for _ in 0..steps {
+        let Some(next) = outgoing
+            .get(&current)
+            .and_then(|next| next.choose(&mut rng))
+        else {
+            break;
+        };
+        current = *next;
+        walk.push(current);
+    }
+    walk
+}
+
+/// Weakly connected component id of every node in `0..dot_count()`.
+///
+/// Ids are contiguous and numbered by the smallest node of each component,
+/// so node 0 is always in component 0. Isolated nodes get components of their own.
+pub fn connected_components(graph: &dyn Graph) -> Vec<usize> {
+    let count = graph.dot_count();
+    let mut parent: Vec<_> = (0..count).collect();
+
+    fn root(parent: &mut [usize], mut node: usize) -> usize {
+        while parent[node] != node {
+            parent[node] = parent[parent[node]];
+            node = parent[node];
+        }
+        node
+    }
+
+    graph.for_each_edge(&mut |from, to| {
+        if from < count && to < count {
+            let (from, to) = (root(&mut parent, from), root(&mut parent, to));
+            parent[from.max(to)] = from.min(to);
+        }
+    });
+
+    let mut ids = HashMap::new();
+    (0..count)
+        .map(|node| {
+            let root = root(&mut parent, node);
+            let next = ids.len();
+            *ids.entry(root).or_insert(next)
+        })
+        .collect()
+}
+
+/// Edges of a BFS spanning tree for every weakly connected component, as `(parent, child)` pairs.
+///
+/// Each BFS starts at the smallest node of its component and visits neighbors in index order,
+/// trees are ordered the same way as `connected_components` numbers components.
+/// Isolated nodes give empty trees, edges touching nodes past `dot_count()` are ignored.
+pub fn spanning_forest(graph: &dyn Graph) -> Vec<Vec<(usize, usize)>> {
+    let count = graph.dot_count();
+    let mut adjacent = vec![vec![]; count];
+    graph.for_each_edge(&mut |from, to| {
+        if from < count && to < count && from != to {
+            adjacent[from].push(to);
+            adjacent[to].push(from);
+        }
+    });
+    for list in adjacent.iter_mut() {
+        list.sort_unstable();
+        list.dedup();
+    }
+
+    let mut visited = vec![false; count];
+    let mut forest = vec![];
+    let mut queue = VecDeque::new();
+    for root in 0..count {
+        if visited[root] {
+            continue;
+        }
+        visited[root] = true;
+        queue.push_back(root);
+
+        let mut tree = vec![];
+        while let Some(node) = queue.pop_front() {
+            for &next in adjacent[node].iter() {
+                if !visited[next] {
+                    visited[next] = true;
+                    tree.push((node, next));
+                    queue.push_back(next);
+                }
+            }
+        }
+        forest.push(tree);
+    }
+    forest
+}
+
+/// Nodes reachable from `start` in the order a breadth-first search visits them, `start` first.
+///
+/// Follows edges in their direction, neighbors are explored in the order `neighbors` reports them.
+/// Empty when `start` is outside of `0..dot_count()`.
+pub fn bfs(graph: &dyn Graph, start: usize) -> Vec<usize> {
+    let count = graph.dot_count();
+    if start >= count {
+        return vec![];
+    }
+
+    let mut visited = vec![false; count];
+    visited[start] = true;
+    let mut order = vec![];
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        order.push(node);
+        graph.neighbors(node, &mut |next| {
+            if next < count && !visited[next] {
+                visited[next] = true;
+                queue.push_back(next);
+            }
+        });
+    }
+    order
+}
+
+/// Nodes of a path from `from` to `to` with the fewest edges, both ends included.
+///
+/// Edges are followed in their direction and weights are ignored, `from == to` gives `[from]`.
+/// `None` when `to` isn't reachable or either node is outside of `0..dot_count()`.
+pub fn shortest_path(graph: &dyn Graph, from: usize, to: usize) -> Option<Vec<usize>> {
+    let count = graph.dot_count();
+    if from >= count || to >= count {
+        return None;
+    }
+
+    let mut parent = vec![None; count];
+    parent[from] = Some(from);
+    let mut queue = VecDeque::from([from]);
+    while let Some(node) = queue.pop_front() {
+        if node == to {
+            break;
+        }
+        graph.neighbors(node, &mut |next| {
+            if next < count && parent[next].is_none() {
+                parent[next] = Some(node);
+                queue.push_back(next);
+            }
+        });
+    }
+
+    let mut path = vec![to];
+    let mut node = to;
+    while node != from {
+        node = parent[node]?;
+        path.push(node);
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// Path from `from` to `to` with the smallest total weight and that weight, via Dijkstra.
+///
+/// Edges are followed in their direction, undirected ones either way. Negative and `NaN`
+/// weights count as 0, `from == to` gives `[from]` of weight 0. `None` when `to` isn't
+/// reachable or either node is outside of `0..dot_count()`.
+pub fn shortest_weighted_path(
+    graph: &dyn WeightedGraph,
+    from: usize,
+    to: usize,
+) -> Option<(f64, Vec<usize>)> {
+    let count = graph.dot_count();
+    if from >= count || to >= count {
+        return None;
+    }
+
+    let undirected = graph.directedness() == Directedness::Undirected;
+    let mut outgoing = vec![vec![]; count];
+    graph.for_each_weighted_edge(&mut |lhs, rhs, weight| {
+        let weight = (weight as f64).max(0.0);
+        if lhs < count && rhs < count {
+            outgoing[lhs].push((rhs, weight));
+            if undirected {
+                outgoing[rhs].push((lhs, weight));
+            }
+        }
+    });
+
+    let mut distance = vec![f64::INFINITY; count];
+    let mut parent = vec![None; count];
+    let mut done = vec![false; count];
+    distance[from] = 0.0;
+    let mut queue = BinaryHeap::from([Reverse((Distance(0.0), from))]);
+    while let Some(Reverse((Distance(dist), node))) = queue.pop() {
+        if std::mem::replace(&mut done[node], true) {
+            continue;
+        }
+        if node == to {
+            break;
+        }
+        for &(next, weight) in outgoing[node].iter() {
+            if dist + weight < distance[next] {
+                distance[next] = dist + weight;
+                parent[next] = Some(node);
+                queue.push(Reverse((Distance(distance[next]), next)));
+            }
+        }
+    }
+
+    let mut path = vec![to];
+    let mut node = to;
+    while node != from {
+        node = parent[node]?;
+        path.push(node);
+    }
+    path.reverse();
+    Some((distance[to], path))
+}
+
+/// Path length ordered with `total_cmp`, so it can go into a `BinaryHeap`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Distance(f64);
+
+impl Eq for Distance {}
+
+impl PartialOrd for Distance {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Distance {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Node × edge incidence matrix with -1 at the source and +1 at the target of every edge,
+/// or +1 at both ends of every edge for undirected graphs.
+///
+/// Columns follow `for_each_edge` order, one per parallel edge. Directed self-loops give
+/// all-zero columns, undirected ones +2 at their node. Rows cover every node up to the largest
+/// one referenced by an edge.
+pub fn to_incidence_matrix(graph: &dyn Graph) -> Vec<Vec<i8>> {
+    let mut edges = vec![];
+    graph.for_each_edge(&mut |from, to| edges.push((from, to)));
+
+    let undirected = graph.directedness() == Directedness::Undirected;
+    let mut matrix = vec![vec![0; edges.len()]; node_bound(graph)];
+    for (column, (from, to)) in edges.into_iter().enumerate() {
+        if undirected {
+            matrix[from][column] += 1;
+            matrix[to][column] += 1;
+        } else if from != to {
+            matrix[from][column] = -1;
+            matrix[to][column] = 1;
+        }
+    }
+    matrix
+}
+
+/// Nodes ordered so that every edge goes forward, or `None` if the graph has a cycle.
+///
+/// Of the nodes available at each step the smallest goes first, so the order is deterministic.
+/// A self-loop counts as a cycle.
+pub fn topological_sort(graph: &dyn Graph) -> Option<Vec<usize>> {
+    let count = node_bound(graph);
+    let mut incoming = vec![0usize; count];
+    let mut outgoing = vec![vec![]; count];
+    graph.for_each_edge(&mut |from, to| {
+        incoming[to] += 1;
+        outgoing[from].push(to);
+    });
+
+    let mut ready: BinaryHeap<_> = (0..count)
+        .filter(|&node| incoming[node] == 0)
+        .map(Reverse)
+        .collect();
+    let mut order = Vec::with_capacity(count);
+    while let Some(Reverse(node)) = ready.pop() {
+        order.push(node);
+        for &next in outgoing[node].iter() {
+            incoming[next] -= 1;
+            if incoming[next] == 0 {
+                ready.push(Reverse(next));
+            }
+        }
+    }
+
+    (order.len() == count).then_some(order)
+}
+
+/// Heaviest path of a DAG and its total weight, or `None` if the graph has a cycle.
+///
+/// Paths may start at any node, so a graph without edges gives a single node path of weight 0.
+pub fn longest_path_dag(graph: &dyn WeightedGraph) -> Option<(f64, Vec<usize>)> {
+    let order = topological_sort(graph)?;
+
+    let count = order.len();
+    let mut incoming = vec![vec![]; count];
+    graph.for_each_weighted_edge(&mut |from, to, weight| incoming[to].push((from, weight as f64)));
+
+    let mut best = vec![0.0f64; count];
+    let mut previous = vec![None; count];
+    for &node in order.iter() {
+        for &(from, weight) in incoming[node].iter() {
+            if best[from] + weight > best[node] {
+                best[node] = best[from] + weight;
+                previous[node] = Some(from);
+            }
+        }
+    }
+
+    let end = (0..count).max_by(|&lhs, &rhs| best[lhs].total_cmp(&best[rhs]))?;
+    let mut path = vec![end];
+    while let Some(from) = previous[*path.last().expect("path is never empty")] {
+        path.push(from);
+    }
+    path.reverse();
+    Some((best[end], path))
+}
+
+/// Contracts every edge of `matching` at once, giving the coarse graph and the supernode
+/// of every original node.
+///
+/// Supernodes are numbered by their smallest member. Pairs touching a node already matched
+/// by an earlier pair are skipped, edges inside a contracted pair are dropped.
+/// The coarse graph keeps the directedness of `graph` and one edge per original edge,
+/// so edges merged between two supernodes show up as parallel edges.
+pub fn coarsen(
+    graph: &dyn Graph,
+    matching: &[(usize, usize)],
+    backend: GraphBackend,
+) -> (Box<dyn DrawableGraph>, Vec<usize>) {
+    let count = node_bound(graph);
+    let mut partner: Vec<Option<usize>> = vec![None; count];
+    for &(lhs, rhs) in matching {
+        let free = |node: usize| node < count && partner[node].is_none();
+        if lhs != rhs && free(lhs) && free(rhs) {
+            partner[lhs] = Some(rhs);
+            partner[rhs] = Some(lhs);
+        }
+    }
+
+    let mut supernode = vec![usize::MAX; count];
+    let mut next = 0;
+    for node in 0..count {
+        if supernode[node] == usize::MAX {
+            supernode[node] = next;
+            if let Some(other) = partner[node] {
+                supernode[other] = next;
+            }
+            next += 1;
+        }
+    }
+
+    let options = GraphOptions {
+        multiplicity: Multiplicity::Multi,
+        directedness: graph.directedness(),
+        ..Default::default()
+    };
+    let mut coarse = super::with_options(backend, next, options);
+    graph.for_each_edge(&mut |from, to| {
+        if partner[from] != Some(to) {
+            coarse.add_edge(supernode[from], supernode[to]);
+        }
+    });
+    (coarse, supernode)
+}
+
+/// Copy of `graph` keeping only the edges `pred` accepts, all nodes are kept.
+///
+/// The copy has the same directedness and edge weights, parallel edges are copied as they are.
+pub fn filter_edges(
+    graph: &dyn WeightedGraph,
+    pred: impl Fn(usize, usize) -> bool,
+    backend: GraphBackend,
+) -> Box<dyn DrawableGraph> {
+    let options = GraphOptions {
+        multiplicity: Multiplicity::Multi,
+        directedness: graph.directedness(),
+        ..Default::default()
+    };
+    let mut filtered = super::with_options(backend, graph.dot_count(), options);
+    graph.for_each_weighted_edge(&mut |from, to, weight| {
+        if pred(from, to) {
+            filtered.add_weighted_edge(from, to, weight)
+        }
+    });
+    filtered
+}
+
+/// Node count and the edge list relabeled so that isomorphic graphs tend to give the same
+/// output, sorted.
+///
+/// Nodes are told apart by color refinement over in- and out-neighbors, undirected edges
+/// count both ways and come out smaller end first. Nodes refinement
+/// can't tell apart are individualized one at a time, smallest class first, which makes this
+/// a heuristic: graphs with many symmetric nodes may get different forms for isomorphic
+/// inputs. Equal forms relabel one graph into the other, so they do mean isomorphic graphs.
+pub fn canonical_form(graph: &dyn Graph) -> (usize, Vec<(usize, usize)>) {
+    let count = node_bound(graph);
+    let mut edges = vec![];
+    let mut outgoing = vec![vec![]; count];
+    let mut incoming = vec![vec![]; count];
+    graph.for_each_edge(&mut |from, to| edges.push((from, to)));
+    graph.for_each_arc(&mut |from, to| {
+        outgoing[from].push(to);
+        incoming[to].push(from);
+    });
+    let undirected = graph.directedness() == Directedness::Undirected;
+
+    let mut colors = refine(&vec![0; count], &outgoing, &incoming);
+    loop {
+        let mut class_sizes = HashMap::new();
+        for &color in colors.iter() {
+            *class_sizes.entry(color).or_insert(0usize) += 1;
+        }
+        let Some(color) = class_sizes
+            .iter()
+            .filter(|(_, size)| **size > 1)
+            .min_by_key(|(color, size)| (**size, **color))
+            .map(|(color, _)| *color)
+        else {
+            break;
+        };
+
+        let picked = colors
+            .iter()
+            .position(|c| *c == color)
+            .expect("class is not empty");
+        let mut individualized: Vec<_> = colors.iter().map(|color| color * 2 + 1).collect();
+        individualized[picked] -= 1;
+        colors = refine(&individualized, &outgoing, &incoming);
+    }
+
+    let mut form: Vec<_> = edges
+        .into_iter()
+        .map(|(from, to)| (colors[from], colors[to]))
+        .map(|(from, to)| {
+            if undirected {
+                (from.min(to), from.max(to))
+            } else {
+                (from, to)
+            }
+        })
+        .collect();
+    form.sort_unstable();
+    (count, form)
+}
+
+/// Splits color classes by neighbor colors until nothing changes, colors are dense ranks.
+fn refine(initial: &[usize], outgoing: &[Vec<usize>], incoming: &[Vec<usize>]) -> Vec<usize> {
+    let mut colors = rank(initial.to_vec());
+    loop {
+        let signatures = (0..colors.len())
+            .map(|node| {
+                let neighbor_colors = |neighbors: &[usize]| {
+                    let mut res: Vec<_> = neighbors.iter().map(|&other| colors[other]).collect();
+                    res.sort_unstable();
+                    res
+                };
+                (
+                    colors[node],
+                    neighbor_colors(&outgoing[node]),
+                    neighbor_colors(&incoming[node]),
+                )
+            })
+            .collect();
+
+        let refined = rank(signatures);
+        let classes = |colors: &[usize]| colors.iter().copied().max().map_or(0, |max| max + 1);
+        if classes(&refined) == classes(&colors) {
+            return refined;
+        }
+        colors = refined;
+    }
+}
+
+fn rank<T: Ord + Clone>(signatures: Vec<T>) -> Vec<usize> {
+    let mut distinct = signatures.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+    signatures
+        .iter()
+        .map(|sig| {
+            distinct
+                .binary_search(sig)
+                .expect("signature is among distinct ones")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{with_dots_count, with_options};
+
+    fn undirected(edges: &[(usize, usize)]) -> Box<dyn DrawableGraph> {
+        let options = GraphOptions {
+            directedness: Directedness::Undirected,
+            ..Default::default()
+        };
+        let mut graph = with_options(GraphBackend::EdgeList, 3, options);
+        graph.add_edges(edges);
+        graph
+    }
+
+    fn sample(graph: &mut dyn Graph) {
+        for (from, to) in [(0, 1), (1, 2), (2, 0), (0, 4)] {
+            graph.add_edge(from, to);
+        }
+    }
+
+    #[test]
+    fn complement_within_finds_the_missing_edge() {
+        let mut graph = with_dots_count(GraphBackend::EdgeList, 5);
+        for (from, to) in [(0, 1), (2, 1), (0, 3), (1, 3), (0, 0)] {
+            graph.add_edge(from, to);
+        }
+        assert_eq!(
+            complement_within(&*graph, &[0, 1, 2, 3]),
+            vec![(0, 2), (2, 3)]
+        );
+        assert_eq!(complement_within(&*graph, &[3, 1, 0]), vec![]);
+        assert_eq!(complement_within(&*graph, &[1, 2, 3]), vec![(2, 3)]);
+    }
+
+    #[test]
+    fn directedness_is_inferred_from_reverse_edges() {
+        let mut graph = with_dots_count(GraphBackend::EdgeList, 3);
+        for (from, to) in [(0, 1), (1, 0), (1, 2), (2, 1), (2, 2)] {
+            graph.add_edge(from, to);
+        }
+        assert!(!infer_directedness(&*graph));
+
+        graph.add_edge(0, 2);
+        assert!(infer_directedness(&*graph));
+    }
+
+    #[test]
+    fn star_is_disassortative() {
+        let mut graph = with_dots_count(GraphBackend::EdgeList, 5);
+        for to in 1..5 {
+            graph.add_edge(0, to);
+        }
+
+        let assortativity = degree_assortativity(&*graph);
+        assert!(assortativity < 0.0, "{assortativity}");
+        assert!((assortativity + 1.0).abs() < 1e-9, "{assortativity}");
+        assert!(degree_assortativity(&*with_dots_count(GraphBackend::EdgeList, 5)).is_nan());
+    }
+
+    #[test]
+    fn seeded_walk_is_reproducible_and_follows_edges() {
+        let mut graph = with_dots_count(GraphBackend::EdgeList, 10);
+        for (from, to) in [(0, 1), (1, 2), (2, 0), (0, 4), (4, 0)] {
+            graph.add_edge(from, to);
+        }
+        let mut edges = HashSet::new();
+        graph.for_each_edge(&mut |from, to| {
+            edges.insert((from, to));
+        });
+
+        let walk = random_walk(&*graph, 0, 50, 3);
+        assert_eq!(walk, random_walk(&*graph, 0, 50, 3));
+        assert_eq!(walk.len(), 51);
+        assert_eq!(walk[0], 0);
+        assert!(
+            walk.windows(2).all(|pair| edges.contains(&(pair[0], pair[1]))),
+            "{walk:?}"
+        );
+
+        assert_eq!(random_walk(&*graph, 3, 50, 3), vec![3]);
+    }
+
+    #[test]
+    fn critical_path_of_task_graph() {
+        let mut graph = with_dots_count(GraphBackend::EdgeList, 5);
+        graph.add_weighted_edge(0, 1, 3.0);
+        graph.add_weighted_edge(0, 2, 2.0);
+        graph.add_weighted_edge(1, 3, 4.0);
+        graph.add_weighted_edge(2, 3, 1.0);
+        graph.add_weighted_edge(3, 4, 2.0);
+        graph.add_weighted_edge(2, 4, 5.0);
+
+        assert_eq!(longest_path_dag(&*graph), Some((9.0, vec![0, 1, 3, 4])));
+
+        graph.add_edge(4, 0);
+        assert_eq!(longest_path_dag(&*graph), None);
+    }
+
+    #[test]
+    fn coarsening_a_matching_merges_its_pairs() {
+        let mut graph = with_dots_count(GraphBackend::EdgeList, 6);
+        for (from, to) in [(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 0), (0, 3)] {
+            graph.add_edge(from, to);
+        }
+
+        let matching = [(0, 1), (1, 2), (3, 2), (4, 5)];
+        let (coarse, supernode) = coarsen(&*graph, &matching, GraphBackend::EdgeList);
+        let mut edges = vec![];
+        coarse.for_each_edge(&mut |from, to| edges.push((from, to)));
+
+        assert_eq!(supernode, vec![0, 0, 1, 1, 2, 2]);
+        assert_eq!(coarse.dot_count(), 3);
+        assert_eq!(edges, vec![(0, 1), (1, 2), (2, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn coarsening_keeps_undirected_edges_undirected() {
+        let graph = undirected(&[(0, 1), (2, 1), (2, 0)]);
+
+        let (coarse, supernode) = coarsen(&*graph, &[(1, 0)], GraphBackend::Matrix);
+
+        assert_eq!(supernode, vec![0, 0, 1]);
+        assert_eq!(coarse.directedness(), Directedness::Undirected);
+        assert_eq!(coarse.edge_multiplicity(1, 0), 2);
+    }
+
+    #[test]
+    fn filtering_self_loops_keeps_nodes_weights_and_directedness() {
+        let mut graph = undirected(&[(1, 0), (2, 2)]);
+        graph.add_weighted_edge(2, 1, 4.0);
+
+        let filtered = filter_edges(
+            graph.as_weighted(),
+            |from, to| from != to,
+            GraphBackend::Matrix,
+        );
+        let mut edges = vec![];
+        filtered.for_each_weighted_edge(&mut |from, to, weight| edges.push((from, to, weight)));
+
+        assert_eq!(filtered.dot_count(), 3);
+        assert_eq!(filtered.directedness(), Directedness::Undirected);
+        assert_eq!(edges, vec![(0, 1, 1.0), (1, 2, 4.0)]);
+    }
+
+    #[test]
+    fn relabeled_triangles_share_a_canonical_form() {
+        let build = |edges: [(usize, usize); 4]| {
+            let mut graph = with_dots_count(GraphBackend::EdgeList, 4);
+            for (from, to) in edges {
+                graph.add_edge(from, to);
+            }
+            graph
+        };
+        let graph = build([(0, 1), (1, 2), (2, 0), (0, 3)]);
+        let relabeled = build([(3, 2), (2, 0), (0, 3), (3, 1)]);
+        let inward = build([(0, 1), (1, 2), (2, 0), (3, 0)]);
+
+        let form = canonical_form(&*graph);
+        assert_eq!(form, canonical_form(&*relabeled));
+        assert_ne!(form, canonical_form(&*inward));
+
+        let mut lonely = with_dots_count(GraphBackend::EdgeList, 5);
+        lonely.add_edges(&[(0, 1), (1, 2), (2, 0), (0, 3)]);
+        assert_ne!(form, canonical_form(lonely.as_graph()));
+    }
+
+    #[test]
+    fn sample_forest_has_one_tree_with_edges() {
+        let mut graph = with_dots_count(GraphBackend::EdgeList, 10);
+        sample(graph.as_mut());
+
+        let forest = spanning_forest(&*graph);
+        assert_eq!(forest.len(), 7);
+        assert_eq!(forest[0], vec![(0, 1), (0, 2), (0, 4)]);
+        assert!(forest[1..].iter().all(|tree| tree.is_empty()), "{forest:?}");
+    }
+
+    #[test]
+    fn triangle_is_one_clique() {
+        let mut graph = with_dots_count(GraphBackend::Matrix, 3);
+        graph.add_edges(&[(0, 1), (1, 2), (2, 0)]);
+        assert_eq!(maximal_cliques(graph.as_graph()), Some(vec![vec![0, 1, 2]]));
+    }
+
+    #[test]
+    fn overlapping_cliques_share_nodes() {
+        let mut graph = with_dots_count(GraphBackend::EdgeList, 6);
+        graph.add_edges(&[(0, 1), (1, 2), (0, 2), (1, 3), (2, 3), (3, 4)]);
+        assert_eq!(
+            maximal_cliques(graph.as_graph()),
+            Some(vec![vec![0, 1, 2], vec![1, 2, 3], vec![3, 4], vec![5]])
+        );
+    }
+
+    #[test]
+    fn cliques_of_too_large_graph_are_refused() {
+        let graph = with_dots_count(GraphBackend::EdgeList, MAX_CLIQUE_NODES + 1);
+        assert_eq!(maximal_cliques(graph.as_graph()), None);
+
+        let graph = with_dots_count(GraphBackend::EdgeList, MAX_CLIQUE_NODES);
+        assert_eq!(
+            maximal_cliques(graph.as_graph()).map(|c| c.len()),
+            Some(MAX_CLIQUE_NODES)
+        );
+    }
+
+    #[test]
+    fn critical_path_skips_a_negative_start() {
+        let mut graph = with_dots_count(GraphBackend::EdgeList, 3);
+        graph.add_weighted_edge(0, 1, -2.0);
+        graph.add_weighted_edge(1, 2, 5.0);
+
+        assert_eq!(
+            longest_path_dag(graph.as_weighted()),
+            Some((5.0, vec![1, 2]))
+        );
+    }
+
+    #[test]
+    fn incidence_matrix_of_sample_graph() {
+        let mut graph = with_dots_count(GraphBackend::EdgeList, 10);
+        sample(graph.as_mut());
+
+        let matrix = to_incidence_matrix(graph.as_graph());
+        assert_eq!(matrix.len(), 10);
+        assert!(matrix.iter().all(|row| row.len() == 4));
+        assert_eq!(matrix[0], vec![-1, 0, 1, -1]);
+        assert_eq!(matrix[4], vec![0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn undirected_incidence_matrix_marks_both_ends() {
+        let mut graph = undirected(&[]);
+        sample(graph.as_mut());
+
+        let matrix = to_incidence_matrix(graph.as_graph());
+        assert_eq!(matrix.len(), 5);
+        assert_eq!(matrix[0], vec![1, 0, 1, 1]);
+        assert_eq!(matrix[2], vec![0, 1, 1, 0]);
+        assert!((0..4).all(|column| matrix.iter().map(|row| row[column]).sum::<i8>() == 2));
+    }
+
+    #[test]
+    fn random_walk_follows_undirected_edges_backwards() {
+        let graph = undirected(&[(2, 0)]);
+        assert_eq!(random_walk(graph.as_graph(), 2, 2, 7), vec![2, 0, 2]);
+    }
+
+    #[test]
+    fn canonical_form_ignores_relabeling_of_undirected_path() {
+        let lhs = undirected(&[(0, 1), (1, 2)]);
+        let rhs = undirected(&[(2, 0), (0, 1)]);
+        assert_eq!(
+            canonical_form(lhs.as_graph()),
+            canonical_form(rhs.as_graph())
+        );
+    }
+
+    #[test]
+    fn weighted_path_prefers_lighter_detour() {
+        let mut graph = with_dots_count(GraphBackend::AdjacencyList, 3);
+        graph.add_weighted_edge(0, 2, 100.0);
+        graph.add_weighted_edge(0, 1, 1.0);
+        graph.add_weighted_edge(1, 2, 1.0);
+
+        assert_eq!(
+            shortest_weighted_path(graph.as_weighted(), 0, 2),
+            Some((2.0, vec![0, 1, 2]))
+        );
+        assert_eq!(shortest_path(graph.as_graph(), 0, 2), Some(vec![0, 2]));
+        assert_eq!(shortest_weighted_path(graph.as_weighted(), 2, 0), None);
+    }
+}
@@ -0,0 +1,57 @@
+use std::fmt;
+
+use super::{DrawableGraph, GraphBackend};
+
+/// Failure to read a graph from CSV, with the 1-based line of the offending row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid edge CSV at line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+/// Reads a graph from `from,to` rows of node indices, one edge per row.
+///
+/// The first line is taken for a header and skipped unless it's an edge itself.
+/// Whitespace around values and blank lines are ignored.
+pub fn from_csv(text: &str, backend: GraphBackend) -> Result<Box<dyn DrawableGraph>, CsvError> {
+    let mut graph = super::with_dots_count(backend, 0);
+    for (idx, row) in text.lines().enumerate() {
+        if row.trim().is_empty() {
+            continue;
+        }
+
+        match parse_row(row) {
+            Ok((from, to)) => graph.add_edge(from, to),
+            Err(_) if idx == 0 => continue,
+            Err(message) => {
+                return Err(CsvError {
+                    line: idx + 1,
+                    message,
+                })
+            }
+        }
+    }
+    Ok(graph)
+}
+
+fn parse_row(row: &str) -> Result<(usize, usize), String> {
+    let columns: Vec<_> = row.split(',').map(str::trim).collect();
+    let [from, to] = columns[..] else {
+        return Err(format!("expected 2 columns, got {}", columns.len()));
+    };
+
+    let parse = |value: &str| {
+        value
+            .parse::<usize>()
+            .map_err(|_| format!("'{value}' is not a node index"))
+    };
+    Ok((parse(from)?, parse(to)?))
+}
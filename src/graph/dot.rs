@@ -0,0 +1,343 @@
+use std::{collections::HashMap, fmt, fmt::Write};
+
+use super::{Directedness, DrawableGraph, Graph, GraphBackend, GraphOptions};
+
+/// Failure to read a graph from DOT, with the line it was detected at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for DotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid DOT at line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for DotError {}
+
+/// Reads a `graph` or `digraph` in a subset of Graphviz DOT.
+///
+/// Supports node statements, edge chains like `a -> b -> c` and comments.
+/// Attributes are accepted and ignored, subgraphs and ports are rejected.
+/// Nodes get ids in the order they first appear, a `graph` becomes an undirected one.
+pub fn from_dot(text: &str, backend: GraphBackend) -> Result<Box<dyn DrawableGraph>, DotError> {
+    let tokens = tokenize(text)?;
+    let parsed = Parser::new(&tokens).parse_graph()?;
+
+    let options = GraphOptions {
+        directedness: parsed.directedness,
+        ..Default::default()
+    };
+    let mut graph = super::with_options(backend, parsed.names.len(), options);
+    for (from, to) in parsed.edges {
+        graph.add_edge(from, to);
+    }
+    Ok(graph)
+}
+
+/// Writes the graph as DOT with nodes named by their indices.
+///
+/// Every node in `0..dot_count()` is declared so isolated ones survive a round trip
+/// through `from_dot`, edges follow in `for_each_edge` order.
+pub fn to_dot(graph: &dyn Graph) -> String {
+    let (kind, op) = match graph.directedness() {
+        Directedness::Directed => ("digraph", "->"),
+        Directedness::Undirected => ("graph", "--"),
+    };
+
+    let mut dot = format!("{kind} {{\n");
+    for node in 0..graph.dot_count() {
+        let _ = writeln!(dot, "    {node};");
+    }
+    graph.for_each_edge(&mut |from, to| {
+        let _ = writeln!(dot, "    {from} {op} {to};");
+    });
+    dot.push_str("}\n");
+    dot
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Id(String),
+    /// `->` or `--`.
+    EdgeOp(Directedness),
+    Punct(char),
+}
+
+fn tokenize(text: &str) -> Result<Vec<(Token, usize)>, DotError> {
+    let mut tokens = vec![];
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+    let mut line_start = true;
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\n' => {
+                line += 1;
+                line_start = true;
+                continue;
+            }
+            ch if ch.is_whitespace() => continue,
+            // C preprocessor output lines, Graphviz ignores them.
+            '#' if line_start => while chars.next_if(|ch| *ch != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|ch| *ch != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let start = line;
+                let mut prev = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if prev == '*' => break,
+                        Some(ch) => {
+                            line += (ch == '\n') as usize;
+                            prev = ch;
+                        }
+                        None => return Err(error(start, "unterminated comment")),
+                    }
+                }
+            }
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                tokens.push((Token::EdgeOp(Directedness::Directed), line));
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                chars.next();
+                tokens.push((Token::EdgeOp(Directedness::Undirected), line));
+            }
+            '{' | '}' | '[' | ']' | ';' | ',' | '=' | ':' => tokens.push((Token::Punct(ch), line)),
+            '"' => {
+                let start = line;
+                let mut id = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') if chars.peek() == Some(&'"') => id.extend(chars.next()),
+                        Some(ch) => {
+                            line += (ch == '\n') as usize;
+                            id.push(ch);
+                        }
+                        None => return Err(error(start, "unterminated string")),
+                    }
+                }
+                tokens.push((Token::Id(id), start));
+            }
+            ch if is_id_char(ch) || ch == '-' || ch == '.' => {
+                let mut id = String::from(ch);
+                while let Some(ch) = chars.next_if(|ch| is_id_char(*ch) || *ch == '.') {
+                    id.push(ch);
+                }
+                tokens.push((Token::Id(id), line));
+            }
+            ch => return Err(error(line, format!("unexpected character '{ch}'"))),
+        }
+        line_start = false;
+    }
+    Ok(tokens)
+}
+
+fn is_id_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+fn error(line: usize, message: impl Into<String>) -> DotError {
+    DotError {
+        line,
+        message: message.into(),
+    }
+}
+
+struct ParsedGraph {
+    directedness: Directedness,
+    names: HashMap<String, usize>,
+    edges: Vec<(usize, usize)>,
+}
+
+struct Parser<'a> {
+    tokens: &'a [(Token, usize)],
+    pos: usize,
+    names: HashMap<String, usize>,
+    edges: Vec<(usize, usize)>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(tokens: &'a [(Token, usize)]) -> Self {
+        Self {
+            tokens,
+            pos: 0,
+            names: HashMap::new(),
+            edges: vec![],
+        }
+    }
+
+    fn parse_graph(mut self) -> Result<ParsedGraph, DotError> {
+        if self.peek_keyword("strict") {
+            self.pos += 1;
+        }
+        let directedness = match self.next_id()? {
+            id if id.eq_ignore_ascii_case("digraph") => Directedness::Directed,
+            id if id.eq_ignore_ascii_case("graph") => Directedness::Undirected,
+            id => return Err(self.error(format!("expected 'graph' or 'digraph', got '{id}'"))),
+        };
+        if let Some(Token::Id(_)) = self.peek() {
+            self.pos += 1;
+        }
+        self.expect('{')?;
+
+        while self.peek() != Some(&Token::Punct('}')) {
+            self.parse_statement(directedness)?;
+        }
+        self.expect('}')?;
+        if self.pos != self.tokens.len() {
+            return Err(self.error("unexpected tokens after the graph"));
+        }
+
+        Ok(ParsedGraph {
+            directedness,
+            names: self.names,
+            edges: self.edges,
+        })
+    }
+
+    fn parse_statement(&mut self, directedness: Directedness) -> Result<(), DotError> {
+        if ["graph", "node", "edge"]
+            .iter()
+            .any(|kw| self.peek_keyword(kw))
+        {
+            self.pos += 1;
+            self.skip_attributes()?;
+        } else if self.peek_keyword("subgraph") || self.peek() == Some(&Token::Punct('{')) {
+            return Err(self.error("subgraphs are not supported"));
+        } else {
+            let first = self.next_id()?;
+            if self.peek() == Some(&Token::Punct('=')) {
+                self.pos += 1;
+                self.next_id()?;
+            } else {
+                let mut from = self.node_id(first);
+                while let Some(Token::EdgeOp(op)) = self.peek() {
+                    if *op != directedness {
+                        return Err(self.error(match directedness {
+                            Directedness::Directed => "'--' used in a digraph",
+                            Directedness::Undirected => "'->' used in a graph",
+                        }));
+                    }
+                    self.pos += 1;
+                    let to = self.next_id()?;
+                    let to = self.node_id(to);
+                    self.edges.push((from, to));
+                    from = to;
+                }
+                if self.peek() == Some(&Token::Punct(':')) {
+                    return Err(self.error("ports are not supported"));
+                }
+                self.skip_attributes()?;
+            }
+        }
+
+        if let Some(Token::Punct(';' | ',')) = self.peek() {
+            self.pos += 1;
+        }
+        Ok(())
+    }
+
+    /// Skips `[name = value, ...]` lists following a statement, any number of them.
+    fn skip_attributes(&mut self) -> Result<(), DotError> {
+        while self.peek() == Some(&Token::Punct('[')) {
+            self.pos += 1;
+            loop {
+                match self.peek() {
+                    Some(Token::Punct(']')) => {
+                        self.pos += 1;
+                        break;
+                    }
+                    Some(Token::Id(_) | Token::Punct('=' | ',' | ';')) => self.pos += 1,
+                    Some(_) => return Err(self.error("unexpected token in attribute list")),
+                    None => return Err(self.error("unterminated attribute list")),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn node_id(&mut self, name: String) -> usize {
+        let next = self.names.len();
+        *self.names.entry(name).or_insert(next)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Id(id)) if id.eq_ignore_ascii_case(keyword))
+    }
+
+    fn next_id(&mut self) -> Result<String, DotError> {
+        match self.peek() {
+            Some(Token::Id(id)) => {
+                let id = id.clone();
+                self.pos += 1;
+                Ok(id)
+            }
+            Some(_) => Err(self.error("expected an identifier")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn expect(&mut self, punct: char) -> Result<(), DotError> {
+        if self.peek() != Some(&Token::Punct(punct)) {
+            return Err(self.error(format!("expected '{punct}'")));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn error(&self, message: impl Into<String>) -> DotError {
+        let line = self
+            .tokens
+            .get(self.pos)
+            .or(self.tokens.last())
+            .map_or(1, |(_, line)| *line);
+        error(line, message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(graph: &dyn Graph) -> Vec<(usize, usize)> {
+        let mut edges = vec![];
+        graph.for_each_edge(&mut |from, to| edges.push((from, to)));
+        edges
+    }
+
+    #[test]
+    fn export_round_trips_through_import() {
+        let sources = [
+            "digraph {\n  a -> b -> c; // chain\n  c -> a [weight=2];\n  lonely;\n}",
+            "graph g { x -- y; y -- z; z -- x; w }",
+        ];
+        for source in sources {
+            let imported = from_dot(source, GraphBackend::EdgeList).unwrap();
+            let exported = to_dot(imported.as_graph());
+            let reimported = from_dot(&exported, GraphBackend::EdgeList).unwrap();
+
+            assert_eq!(imported.dot_count(), 4, "{exported}");
+            assert_eq!(reimported.dot_count(), 4, "{exported}");
+            assert_eq!(
+                reimported.directedness(),
+                imported.directedness(),
+                "{exported}"
+            );
+            assert_eq!(
+                edges(reimported.as_graph()),
+                edges(imported.as_graph()),
+                "{exported}"
+            );
+            assert_eq!(to_dot(reimported.as_graph()), exported);
+        }
+    }
+}
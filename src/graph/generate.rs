@@ -0,0 +1,40 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::{DrawableGraph, GraphBackend};
+
+/// Erdős–Rényi random graph on `n` nodes where each of the `n * (n - 1) / 2` node pairs
+/// gets an edge with probability `p`, drawn from an RNG seeded by `seed`.
+///
+/// Edges go from the smaller node to the larger one, there are no self-loops or
+/// parallel edges. `p` is clamped to `0.0..=1.0`, a NaN `p` gives no edges.
+pub fn gnp(backend: GraphBackend, n: usize, p: f64, seed: u64) -> Box<dyn DrawableGraph> {
+    let p = if p.is_nan() { 0.0 } else { p.clamp(0.0, 1.0) };
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut edges = vec![];
+    for from in 0..n {
+        for to in from + 1..n {
+            if rng.gen_bool(p) {
+                edges.push((from, to));
+            }
+        }
+    }
+
+    let mut graph = super::with_dots_count(backend, n);
+    graph.add_edges(&edges);
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nan_probability_gives_no_edges() {
+        let graph = gnp(GraphBackend::EdgeList, 5, f64::NAN, 1);
+        let mut edges = 0;
+        graph.for_each_edge(&mut |_, _| edges += 1);
+
+        assert_eq!(graph.dot_count(), 5);
+        assert_eq!(edges, 0);
+    }
+}
@@ -0,0 +1,180 @@
+#[cfg(feature = "serde")]
+use std::fmt;
+
+use super::{DrawableGraph, Graph, GraphBackend};
+
+/// Largest node count `from_json` accepts, a larger `dots` or node index is rejected
+/// instead of allocating room for it.
+#[cfg(feature = "serde")]
+pub const MAX_JSON_NODES: usize = 1 << 20;
+
+/// Node count and edges of a graph, the form `to_json` writes and `from_json` reads.
+///
+/// With the `serde` feature it serializes to `{"dots": ..., "edges": [[from, to], ...]}`
+/// through any serde format, `from_json` and `to_json` go through it with `serde_json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GraphData {
+    pub dots: usize,
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl GraphData {
+    /// Edges in `for_each_edge` order, directedness and weights are not kept.
+    pub fn of(graph: &dyn Graph) -> Self {
+        let mut edges = vec![];
+        graph.for_each_edge(&mut |from, to| edges.push((from, to)));
+        Self {
+            dots: graph.dot_count(),
+            edges,
+        }
+    }
+
+    pub fn into_graph(self, backend: GraphBackend) -> Box<dyn DrawableGraph> {
+        let mut graph = super::with_dots_count(backend, self.dots);
+        for (from, to) in self.edges {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+}
+
+/// Failure to read a graph from JSON, with the line and column it was detected at if any.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonError {
+    /// Line and column as `serde_json` reports them, `None` for well-formed JSON describing
+    /// a graph that isn't accepted.
+    pub position: Option<(usize, usize)>,
+    pub message: String,
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.position {
+            Some((line, column)) => write!(
+                f,
+                "invalid graph JSON at line {line}, column {column}: {}",
+                self.message
+            ),
+            None => write!(f, "invalid graph JSON: {}", self.message),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::error::Error for JsonError {}
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for JsonError {
+    fn from(err: serde_json::Error) -> Self {
+        // `serde_json` appends the position to its messages, it's kept separately here.
+        let message = err.to_string();
+        let message = match message.rfind(" at line ") {
+            Some(end) if err.line() > 0 => message[..end].to_owned(),
+            _ => message,
+        };
+        Self {
+            position: (err.line() > 0).then(|| (err.line(), err.column())),
+            message,
+        }
+    }
+}
+
+/// Reads a graph from `{"dots": <count>, "edges": [[from, to], ...]}`.
+///
+/// `dots` may be omitted, then only nodes referenced by edges exist.
+/// Graphs with more than `MAX_JSON_NODES` nodes are rejected.
+#[cfg(feature = "serde")]
+pub fn from_json(text: &str, backend: GraphBackend) -> Result<Box<dyn DrawableGraph>, JsonError> {
+    let data: GraphData = serde_json::from_str(text)?;
+    if data.dots > MAX_JSON_NODES {
+        return Err(semantic(format!(
+            "\"dots\" is {}, at most {MAX_JSON_NODES} nodes are supported",
+            data.dots
+        )));
+    }
+    let largest = data
+        .edges
+        .iter()
+        .enumerate()
+        .find(|(_, &(from, to))| from.max(to) >= MAX_JSON_NODES);
+    if let Some((idx, &(from, to))) = largest {
+        return Err(semantic(format!(
+            "edge #{idx} refers to node {}, at most {MAX_JSON_NODES} nodes are supported",
+            from.max(to)
+        )));
+    }
+    Ok(data.into_graph(backend))
+}
+
+/// Writes the graph in the format `from_json` reads, see `GraphData::of` for what's kept.
+#[cfg(feature = "serde")]
+pub fn to_json(graph: &dyn Graph) -> String {
+    serde_json::to_string(&GraphData::of(graph)).expect("graph data always serializes")
+}
+
+#[cfg(feature = "serde")]
+fn semantic(message: impl Into<String>) -> JsonError {
+    JsonError {
+        position: None,
+        message: message.into(),
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use clap::ValueEnum;
+
+    use super::*;
+
+    #[test]
+    fn round_trips_on_every_backend() {
+        let data = GraphData {
+            dots: 6,
+            edges: vec![(0, 1), (2, 0), (4, 4), (1, 3)],
+        };
+        for &backend in GraphBackend::value_variants() {
+            let graph = data.clone().into_graph(backend);
+            let json = to_json(graph.as_graph());
+            let read = from_json(&json, backend).expect("to_json output is valid");
+            assert_eq!(
+                GraphData::of(read.as_graph()),
+                GraphData::of(graph.as_graph()),
+                "{backend:?}: {json}"
+            );
+        }
+    }
+
+    #[test]
+    fn errors_carry_their_position() {
+        let err =
+            from_json("{\"dots\": 3,\n \"edges\": [[0]]}", GraphBackend::EdgeList).unwrap_err();
+        assert_eq!(err.position.map(|(line, _)| line), Some(2));
+        assert!(!err.message.contains("at line"), "{err}");
+    }
+
+    #[test]
+    fn deep_nesting_is_an_error() {
+        let text = "[".repeat(200_000);
+        assert!(from_json(&text, GraphBackend::EdgeList).is_err());
+    }
+
+    #[test]
+    fn huge_graphs_are_rejected() {
+        let too_many = MAX_JSON_NODES + 1;
+        for text in [
+            format!(r#"{{"dots": {too_many}}}"#),
+            format!(r#"{{"edges": [[0, {MAX_JSON_NODES}]]}}"#),
+        ] {
+            let err = from_json(&text, GraphBackend::AdjacencyList).unwrap_err();
+            assert!(err.message.contains("at most"), "{text}: {err}");
+        }
+        assert!(from_json(r#"{"dots": 1e15}"#, GraphBackend::AdjacencyList).is_err());
+
+        let text = format!(r#"{{"dots": {MAX_JSON_NODES}}}"#);
+        assert!(from_json(&text, GraphBackend::EdgeList).is_ok());
+    }
+}
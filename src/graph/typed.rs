@@ -0,0 +1,110 @@
+use std::{collections::HashMap, fmt::Debug, hash::Hash};
+
+use super::{DrawableGraph, GraphBackend};
+
+/// Graph over arbitrary node values, backed by one of the `usize`-indexed backends.
+///
+/// Node values get contiguous indices in order of first appearance.
+#[derive(Debug)]
+pub struct TypedGraph<N> {
+    graph: Box<dyn DrawableGraph>,
+    nodes: Vec<N>,
+    ids: HashMap<N, usize>,
+}
+
+impl<N: Eq + Hash + Clone + Debug> TypedGraph<N> {
+    pub fn new(backend: GraphBackend) -> Self {
+        Self {
+            graph: super::with_dots_count(backend, 0),
+            nodes: vec![],
+            ids: HashMap::new(),
+        }
+    }
+
+    pub fn add_node(&mut self, node: &N) -> usize {
+        if let Some(&id) = self.ids.get(node) {
+            return id;
+        }
+
+        let id = self.graph.add_node();
+        self.nodes.push(node.clone());
+        self.ids.insert(node.clone(), id);
+        id
+    }
+
+    pub fn add_edge(&mut self, from: &N, to: &N) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        self.graph.add_edge(from, to);
+    }
+
+    pub fn index_of(&self, node: &N) -> Option<usize> {
+        self.ids.get(node).copied()
+    }
+
+    pub fn node(&self, index: usize) -> Option<&N> {
+        self.nodes.get(index)
+    }
+
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    pub fn dot_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn neighbors(&self, node: &N) -> Vec<N> {
+        let Some(id) = self.index_of(node) else {
+            return vec![];
+        };
+
+        let mut res = vec![];
+        self.graph
+            .neighbors(id, &mut |to| res.push(self.nodes[to].clone()));
+        res
+    }
+
+    pub fn for_each_edge(&self, cb: &mut dyn FnMut(&N, &N)) {
+        self.graph
+            .for_each_edge(&mut |from, to| cb(&self.nodes[from], &self.nodes[to]))
+    }
+
+    pub fn graph(&self) -> &dyn DrawableGraph {
+        self.graph.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbors_are_looked_up_by_value() {
+        let mut graph = TypedGraph::new(GraphBackend::AdjacencyList);
+        let [a, b, c] = ["a", "b", "c"].map(String::from);
+        graph.add_edge(&a, &b);
+        graph.add_edge(&a, &c);
+        graph.add_edge(&b, &c);
+
+        assert_eq!(graph.neighbors(&a), vec![b.clone(), c.clone()]);
+        assert!(graph.neighbors(&c).is_empty());
+        assert!(graph.neighbors(&"d".to_string()).is_empty());
+    }
+
+    #[test]
+    fn isolated_nodes_grow_the_backing_graph() {
+        for backend in [
+            GraphBackend::EdgeList,
+            GraphBackend::Matrix,
+            GraphBackend::AdjacencyList,
+        ] {
+            let mut graph = TypedGraph::new(backend);
+            graph.add_edge(&"a".to_string(), &"b".to_string());
+            assert_eq!(graph.add_node(&"c".to_string()), 2);
+
+            assert_eq!(graph.dot_count(), 3);
+            assert_eq!(graph.graph().dot_count(), 3, "{backend:?}");
+        }
+    }
+}
@@ -1,73 +1,1057 @@
+use std::{
+    cell::RefCell,
+    fmt, io,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    rc::Rc,
+    str::FromStr,
+    time::Duration,
+};
+
 use iced::Application;
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+
+use crate::layout::Layout;
+
+mod legend;
+
+pub use legend::{legend_rows, weight_legend, weight_width, LegendEntry, LegendRow};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Coord {
-    x: f32,
-    y: f32,
+    pub x: f32,
+    pub y: f32,
 }
 
-#[derive(Debug, Default)]
-struct GraphicsHolder {
+impl Coord {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn scaled(self, width: f32, height: f32) -> Self {
+        Self::new(self.x * width, self.y * height)
+    }
+
+    pub fn distance(self, other: Coord) -> f32 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
+/// Distance from `point` to the closest point of the segment between `from` and `to`.
+pub fn distance_to_segment(point: Coord, from: Coord, to: Coord) -> f32 {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    let len_sq = dx * dx + dy * dy;
+    if len_sq == 0.0 {
+        return point.distance(from);
+    }
+
+    let t = (((point.x - from.x) * dx + (point.y - from.y) * dy) / len_sq).clamp(0.0, 1.0);
+    point.distance(Coord::new(from.x + t * dx, from.y + t * dy))
+}
+
+/// Distance between the middles of neighboring edges connecting the same two nodes, in pixels.
+pub const PARALLEL_EDGE_SPACING: f32 = 14.0;
+
+/// Bend of each of the `count` edges connecting the same two nodes, in pixels to the left
+/// of the edge going from the smaller node to the larger one.
+///
+/// Bends are spread evenly around zero, so a lone edge and the middle one of an odd
+/// count stay straight.
+pub fn parallel_bends(count: usize) -> impl DoubleEndedIterator<Item = f32> {
+    let middle = count.saturating_sub(1) as f32 / 2.0;
+    (0..count).map(move |idx| (idx as f32 - middle) * PARALLEL_EDGE_SPACING)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const WHITE: Color = Color::rgb(0xff, 0xff, 0xff);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 0xff }
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Opaque color from a hue in degrees, saturation and value in `0.0..=1.0`.
+    pub fn hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let chroma = value * saturation;
+        let sector = hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+        let (r, g, b) = match sector as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let channel = |c: f32| ((c + value - chroma) * 255.0).round() as u8;
+        Self::rgb(channel(r), channel(g), channel(b))
+    }
+}
+
+/// Failure to read a color from a `#RRGGBB` or `#RRGGBBAA` string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorError {
+    pub input: String,
+}
+
+impl fmt::Display for ColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid color '{}', expected #RRGGBB or #RRGGBBAA",
+            self.input
+        )
+    }
+}
+
+impl std::error::Error for ColorError {}
+
+impl FromStr for Color {
+    type Err = ColorError;
+
+    /// Reads `#RRGGBB` or `#RRGGBBAA` in hex, the `#` may be left out.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = || ColorError {
+            input: text.to_owned(),
+        };
+        let hex = text.strip_prefix('#').unwrap_or(text);
+        if !matches!(hex.len(), 6 | 8) || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(error());
+        }
+
+        let channel = |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16).map_err(|_| error());
+        let alpha = if hex.len() == 8 { channel(6)? } else { 0xff };
+        Ok(Color::rgba(channel(0)?, channel(2)?, channel(4)?, alpha))
+    }
+}
+
+/// Hue step between consecutive component colors, in degrees.
+const GOLDEN_ANGLE: f32 = 137.508;
+
+/// Dot color of component `idx` when coloring by component.
+///
+/// Hues go around the circle by the golden angle, so every few consecutive components
+/// look clearly different however many there are.
+pub fn component_color(idx: usize) -> Color {
+    Color::hsv(idx as f32 * GOLDEN_ANGLE, 0.55, 0.95)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EdgeStyle {
+    pub color: Color,
+    pub width: f32,
+}
+
+impl Default for EdgeStyle {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            width: 1.0,
+        }
+    }
+}
+
+/// Style of edges on the path given to `RenderConfig::highlight_path`.
+pub const PATH_EDGE_STYLE: EdgeStyle = EdgeStyle {
+    color: Color::rgb(0xe8, 0x30, 0x30),
+    width: 2.5,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DotStyle {
+    pub color: Color,
+    pub radius: f32,
+}
+
+impl Default for DotStyle {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            radius: 5.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Line {
+    /// Index of the dot the line starts at.
+    from: usize,
+    /// Index of the dot the line ends at, where the arrowhead or the self-loop is drawn.
+    to: usize,
+    style: EdgeStyle,
+    /// Set for weighted edges, whose width is then picked from the weight range.
+    weight: Option<f32>,
+    /// How far the middle of the line is bowed to its left in pixels, 0.0 for a straight line.
+    bend: f32,
+}
+
+impl Line {
+    fn is_loop(&self) -> bool {
+        self.from == self.to
+    }
+
+    /// Control point of the quadratic curve a bent line between `from` and `to` in pixels
+    /// is drawn as, `None` for a straight line.
+    fn control(&self, from: Coord, to: Coord) -> Option<Coord> {
+        let len = from.distance(to);
+        if self.bend == 0.0 || len == 0.0 {
+            return None;
+        }
+
+        // The curve passes halfway between the chord middle and the control point.
+        let shift = 2.0 * self.bend / len;
+        Some(Coord::new(
+            (from.x + to.x) / 2.0 + (to.y - from.y) * shift,
+            (from.y + to.y) / 2.0 - (to.x - from.x) * shift,
+        ))
+    }
+}
+
+/// Size of a self-loop circle relative to the dot radius.
+pub const LOOP_RADIUS_RATIO: f32 = 1.2;
+
+/// Center and radius of the circle a self-loop at `dot` is drawn as, all in pixels.
+///
+/// The circle sits on top of the dot, overlapping it slightly so it reads as attached.
+pub fn loop_circle(dot: Coord, dot_radius: f32) -> (Coord, f32) {
+    let radius = dot_radius * LOOP_RADIUS_RATIO;
+    let center = Coord::new(dot.x, dot.y - dot_radius - radius * 0.6);
+    (center, radius)
+}
+
+/// Length of the two strokes forming an arrowhead, in pixels.
+pub const ARROW_LENGTH: f32 = 9.0;
+/// Angle between either arrowhead stroke and the edge, in radians.
+const ARROW_SPREAD: f32 = 0.45;
+
+#[derive(Debug, Clone)]
+pub struct GraphicsHolder {
     dots: Vec<Coord>,
-    lines: Vec<(Coord, Coord)>,
+    /// Fill of every dot, `dot_style.color` unless recolored.
+    dot_colors: Vec<Color>,
+    /// Radius of dots sized on their own, dots past its end use `dot_style.radius`.
+    dot_radii: Vec<f32>,
+    /// Text drawn beside every dot, the dot index unless given explicitly.
+    labels: Vec<String>,
+    lines: Vec<Line>,
+    /// Lines an animation hasn't revealed yet, the next one last.
+    hidden: Vec<Line>,
+    background: Color,
+    dot_style: DotStyle,
+    ids_inside: bool,
+    /// Whether edges get an arrowhead at their `to` end.
+    arrows: bool,
+    /// Delay between lines appearing one by one in a window, without it they're all there at once.
+    animate: Option<Duration>,
+    /// Whether weighted lines get their weight written over them.
+    show_weights: bool,
+    legend: Vec<LegendEntry>,
+}
+
+impl Default for GraphicsHolder {
+    fn default() -> Self {
+        Self::new(&RenderConfig::default())
+    }
+}
+
+impl GraphicsHolder {
+    fn new(config: &RenderConfig) -> Self {
+        Self {
+            dots: vec![],
+            dot_colors: vec![],
+            dot_radii: vec![],
+            labels: vec![],
+            lines: vec![],
+            hidden: vec![],
+            background: config.background,
+            dot_style: config.effective_dot_style(),
+            ids_inside: config.ids_inside,
+            arrows: config.arrows,
+            animate: config.animate,
+            show_weights: config.show_weights,
+            legend: vec![],
+        }
+    }
+
+    /// Copy without what the window toggles hide: lines unless `show_edges`, dots without
+    /// lines unless `show_isolated`. Hidden dots turn transparent so indices stay the same.
+    fn with_toggles(&self, show_edges: bool, show_isolated: bool) -> GraphicsHolder {
+        let mut shown = self.clone();
+        if !show_isolated {
+            let degrees = self.dot_degrees();
+            for (color, degree) in shown.dot_colors.iter_mut().zip(degrees) {
+                if degree == 0 {
+                    color.a = 0;
+                }
+            }
+        }
+        if !show_edges {
+            shown.lines.clear();
+        }
+        shown
+    }
+
+    /// Lines split into runs of consecutive lines sharing a style.
+    fn style_runs(&self) -> impl Iterator<Item = &[Line]> {
+        self.lines.chunk_by(|lhs, rhs| lhs.style == rhs.style)
+    }
+
+    /// Turns weights of weighted lines into widths and builds the legend for them.
+    fn apply_weights(&mut self) {
+        let weights: Vec<_> = self.lines.iter().filter_map(|line| line.weight).collect();
+        self.legend = weight_legend(&weights);
+        if self.legend.is_empty() {
+            return;
+        }
+
+        let min = self.legend[0].weight;
+        let max = self.legend[self.legend.len() - 1].weight;
+        for line in self.lines.iter_mut() {
+            if let Some(weight) = line.weight {
+                line.style.width = weight_width(weight, min, max);
+            }
+        }
+    }
+
+    /// Positions of the dots `line` connects.
+    fn line_ends(&self, line: &Line) -> (Coord, Coord) {
+        (self.dots[line.from], self.dots[line.to])
+    }
+
+    /// Arrowhead for an edge between `from` and `to`, given in pixels: the tip on the boundary
+    /// of the `to` dot and the free ends of the two strokes meeting at it.
+    ///
+    /// `None` with arrows off, or when the dots overlap and there's no boundary to point at.
+    fn arrowhead(&self, line: &Line, from: Coord, to: Coord) -> Option<(Coord, [Coord; 2])> {
+        // Curves end heading away from their control point.
+        let from = line.control(from, to).unwrap_or(from);
+        let radius = self.dot_radius(line.to);
+        let len = from.distance(to);
+        if !self.arrows || len <= 2.0 * radius {
+            return None;
+        }
+
+        let (back_x, back_y) = ((from.x - to.x) / len, (from.y - to.y) / len);
+        let tip = Coord::new(to.x + back_x * radius, to.y + back_y * radius);
+        let wing = |angle: f32| {
+            let (sin, cos) = angle.sin_cos();
+            Coord::new(
+                tip.x + (back_x * cos - back_y * sin) * ARROW_LENGTH,
+                tip.y + (back_x * sin + back_y * cos) * ARROW_LENGTH,
+            )
+        };
+        Some((tip, [wing(ARROW_SPREAD), wing(-ARROW_SPREAD)]))
+    }
+
+    /// Weight of a weighted line between `from` and `to`, given in pixels, written at
+    /// the middle of the line or the top of a loop. `None` unless weights are shown.
+    fn weight_label(&self, line: &Line, from: Coord, to: Coord) -> Option<WeightLabel> {
+        let weight = line.weight.filter(|_| self.show_weights)?;
+        let at = if line.is_loop() {
+            let (center, radius) = loop_circle(from, self.dot_radius(line.to));
+            Coord::new(center.x, center.y - radius)
+        } else {
+            match line.control(from, to) {
+                Some(control) => Coord::new(
+                    (from.x + 2.0 * control.x + to.x) / 4.0,
+                    (from.y + 2.0 * control.y + to.y) / 4.0,
+                ),
+                None => Coord::new((from.x + to.x) / 2.0, (from.y + to.y) / 2.0),
+            }
+        };
+        Some(WeightLabel::new(weight, at))
+    }
+
+    /// Moves dot `idx` to `to`, lines attached to it follow since they refer to dots by index.
+    fn move_dot(&mut self, idx: usize, to: Coord) {
+        if let Some(dot) = self.dots.get_mut(idx) {
+            *dot = to;
+        }
+    }
+
+    /// Hides every line for `reveal_line` to bring back in order, if the holder is animated.
+    fn start_animation(&mut self) {
+        if self.animate.is_some() {
+            self.hidden = std::mem::take(&mut self.lines);
+            self.hidden.reverse();
+        }
+    }
+
+    /// Shows the next hidden line, `false` once there are none left.
+    fn reveal_line(&mut self) -> bool {
+        let Some(line) = self.hidden.pop() else {
+            return false;
+        };
+        self.lines.push(line);
+        true
+    }
+
+    /// Delay before the next hidden line is revealed, `None` once the animation is over.
+    fn next_reveal(&self) -> Option<Duration> {
+        self.animate.filter(|_| !self.hidden.is_empty())
+    }
+
+    fn dot_radius(&self, idx: usize) -> f32 {
+        self.dot_radii
+            .get(idx)
+            .copied()
+            .unwrap_or(self.dot_style.radius)
+    }
+
+    /// Number of shown line ends attached to dot `idx`.
+    ///
+    /// Loops count twice, same as `Graph::degree`.
+    fn dot_degree(&self, idx: usize) -> usize {
+        self.lines
+            .iter()
+            .map(|line| (line.from == idx) as usize + (line.to == idx) as usize)
+            .sum()
+    }
+
+    /// `dot_degree` of every dot, in one pass over the lines.
+    fn dot_degrees(&self) -> Vec<usize> {
+        let mut degrees = vec![0; self.dots.len()];
+        for line in self.lines.iter() {
+            degrees[line.from] += 1;
+            degrees[line.to] += 1;
+        }
+        degrees
+    }
+
+    /// Index of the dot under `point`, with dots scaled to a `width` x `height` canvas
+    /// and their radii divided by `zoom`.
+    fn dot_at(&self, point: Coord, width: f32, height: f32, zoom: f32) -> Option<usize> {
+        (0..self.dots.len()).find(|&idx| {
+            self.dots[idx].scaled(width, height).distance(point) <= self.dot_radius(idx) / zoom
+        })
+    }
+
+    /// Index of the line closest to `point` if it's not farther than `threshold`,
+    /// with lines scaled to a `width` x `height` canvas.
+    fn line_at(&self, point: Coord, width: f32, height: f32, threshold: f32) -> Option<usize> {
+        self.lines
+            .iter()
+            .map(|line| {
+                let (from, to) = self.line_ends(line);
+                let (from, to) = (from.scaled(width, height), to.scaled(width, height));
+                match line.control(from, to) {
+                    // Close enough to the curve for picking: the two halves of the chord
+                    // moved to pass through its middle.
+                    Some(control) => {
+                        let middle = Coord::new(
+                            (from.x + 2.0 * control.x + to.x) / 4.0,
+                            (from.y + 2.0 * control.y + to.y) / 4.0,
+                        );
+                        distance_to_segment(point, from, middle)
+                            .min(distance_to_segment(point, middle, to))
+                    }
+                    None => distance_to_segment(point, from, to),
+                }
+            })
+            .enumerate()
+            .filter(|(_, dist)| *dist <= threshold)
+            .min_by(|(_, lhs), (_, rhs)| lhs.total_cmp(rhs))
+            .map(|(idx, _)| idx)
+    }
+}
+
+/// Everything that affects how a graph is turned into a picture.
+///
+/// `RenderConfig::default()` gives a force-directed layout with white dots and edges on a dark background.
+#[derive(Debug, Clone)]
+pub struct RenderConfig {
+    pub(crate) layout: Layout,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) background: Color,
+    pub(crate) dot_style: DotStyle,
+    pub(crate) edge_style: EdgeStyle,
+    pub(crate) ids_inside: bool,
+    pub(crate) arrows: bool,
+    pub(crate) color_components: bool,
+    pub(crate) size_by_degree: bool,
+    pub(crate) path: Vec<usize>,
+    pub(crate) animate: Option<Duration>,
+    pub(crate) show_weights: bool,
+    pub(crate) fit_margin: Option<f32>,
+    pub(crate) fallback: Option<PathBuf>,
+    pub(crate) output: PathBuf,
+}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        Self {
+            layout: Layout::default(),
+            width: 800,
+            height: 600,
+            background: Color::rgb(0x20, 0x20, 0x20),
+            dot_style: DotStyle::default(),
+            edge_style: EdgeStyle::default(),
+            ids_inside: false,
+            arrows: true,
+            color_components: false,
+            size_by_degree: false,
+            path: vec![],
+            animate: None,
+            show_weights: false,
+            fit_margin: Some(DEFAULT_FIT_MARGIN),
+            fallback: Some(PathBuf::from(DEFAULT_FALLBACK_PATH)),
+            output: PathBuf::from(DEFAULT_OUTPUT_PATH),
+        }
+    }
+}
+
+impl RenderConfig {
+    pub fn layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Image size in pixels for the file exporters, windows are sized by the user.
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Image size for the file exporters from a physical size at `dpi` dots per inch,
+    /// `None` unless every dimension is positive and finite.
+    pub fn print_size(self, width: f32, height: f32, unit: LengthUnit, dpi: f32) -> Option<Self> {
+        let (width, height) = pixel_size(width, height, unit, dpi)?;
+        Some(self.size(width, height))
+    }
+
+    pub fn background(mut self, background: Color) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub fn dot_style(mut self, dot_style: DotStyle) -> Self {
+        self.dot_style = dot_style;
+        self
+    }
+
+    /// Style of edges the layout doesn't style on its own.
+    pub fn edge_style(mut self, edge_style: EdgeStyle) -> Self {
+        self.edge_style = edge_style;
+        self
+    }
+
+    /// Writes node indices inside the dots, growing them to at least
+    /// `INSIDE_ID_MIN_RADIUS` so the text fits.
+    pub fn ids_inside(mut self, ids_inside: bool) -> Self {
+        self.ids_inside = ids_inside;
+        self
+    }
+
+    /// Draws an arrowhead where every edge of a directed graph ends,
+    /// undirected graphs never get them.
+    pub fn arrows(mut self, arrows: bool) -> Self {
+        self.arrows = arrows;
+        self
+    }
+
+    /// Fills dots of every connected component with a color of its own,
+    /// see `component_color`.
+    pub fn color_components(mut self, color_components: bool) -> Self {
+        self.color_components = color_components;
+        self
+    }
+
+    /// Grows dots by `DEGREE_RADIUS_STEP` per edge end, up to `MAX_DOT_RADIUS`,
+    /// see `degree_radius`.
+    pub fn size_by_degree(mut self, size_by_degree: bool) -> Self {
+        self.size_by_degree = size_by_degree;
+        self
+    }
+
+    /// Draws edges between consecutive nodes of `path` with `PATH_EDGE_STYLE`, on top of the rest.
+    /// Parallel edges are all highlighted.
+    pub fn highlight_path(mut self, path: Vec<usize>) -> Self {
+        self.path = path;
+        self
+    }
+
+    /// Writes the weight of every edge over it, graphs where every weight is 1.0 get none.
+    pub fn show_weights(mut self, show_weights: bool) -> Self {
+        self.show_weights = show_weights;
+        self
+    }
+
+    /// Window backends start without edges and add one every `per_edge`, in drawing order.
+    /// File exporters always draw every edge.
+    pub fn animate(mut self, per_edge: Option<Duration>) -> Self {
+        self.animate = per_edge;
+        self
+    }
+
+    /// Stretches the layout to fill the canvas less `margin` on every side, as a fraction
+    /// of the canvas. `None` keeps the positions the layout picked.
+    pub fn fit_margin(mut self, margin: Option<f32>) -> Self {
+        self.fit_margin = margin;
+        self
+    }
+
+    /// PNG file written instead when a window can't be opened, e.g. without a display.
+    /// `None` only reports the failure.
+    pub fn fallback(mut self, path: Option<PathBuf>) -> Self {
+        self.fallback = path;
+        self
+    }
+
+    /// PNG file `DrawBackend::Png` writes to.
+    pub fn output(mut self, path: PathBuf) -> Self {
+        self.output = path;
+        self
+    }
+
+    pub(crate) fn effective_dot_style(&self) -> DotStyle {
+        let mut style = self.dot_style;
+        if self.ids_inside {
+            style.radius = style.radius.max(INSIDE_ID_MIN_RADIUS);
+        }
+        style
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthUnit {
+    Inch,
+    Cm,
+}
+
+impl LengthUnit {
+    fn in_inches(self, length: f32) -> f32 {
+        match self {
+            LengthUnit::Inch => length,
+            LengthUnit::Cm => length / 2.54,
+        }
+    }
+}
+
+/// Pixel dimensions of a `width` by `height` figure printed at `dpi`, e.g. 6in × 4in at 300 DPI
+/// is 1800 × 1200. `None` if any of the dimensions isn't positive and finite or doesn't fit.
+pub fn pixel_size(width: f32, height: f32, unit: LengthUnit, dpi: f32) -> Option<(u32, u32)> {
+    let valid = |value: f32| value.is_finite() && value > 0.0;
+    if !(valid(width) && valid(height) && valid(dpi)) {
+        return None;
+    }
+
+    let pixels = |length: f32| {
+        let pixels = (unit.in_inches(length) * dpi).round();
+        (pixels >= 1.0 && pixels <= u32::MAX as f32).then_some(pixels as u32)
+    };
+    Some((pixels(width)?, pixels(height)?))
+}
+
+/// Where `RenderConfig::default()` exports the picture when no window can be opened.
+pub const DEFAULT_FALLBACK_PATH: &str = "graph.png";
+
+/// Where `DrawBackend::Png` writes to unless `RenderConfig::output` says otherwise.
+pub const DEFAULT_OUTPUT_PATH: &str = "graph.png";
+
+/// Free space `RenderConfig::default()` leaves around the layout, as a fraction of the canvas.
+pub const DEFAULT_FIT_MARGIN: f32 = 0.05;
+
+/// Smallest dot radius used when node indices are written inside the dots.
+pub const INSIDE_ID_MIN_RADIUS: f32 = 10.0;
+
+/// Radius a dot grows by per edge end with `RenderConfig::size_by_degree`.
+pub const DEGREE_RADIUS_STEP: f32 = 1.0;
+
+/// Largest radius `RenderConfig::size_by_degree` grows dots to.
+pub const MAX_DOT_RADIUS: f32 = 20.0;
+
+/// Radius of a dot with `degree` edge ends when sizing by degree, dots are never
+/// shrunk below `base`.
+pub fn degree_radius(base: f32, degree: usize) -> f32 {
+    (base + DEGREE_RADIUS_STEP * degree as f32).min(MAX_DOT_RADIUS.max(base))
+}
+
+/// Font size for a `digits` long label to fit inside a dot of the given radius.
+///
+/// Grows linearly with the radius and shrinks for longer labels, assuming glyphs
+/// about 0.6 of the font size wide.
+pub fn inside_font_size(radius: f32, digits: usize) -> f32 {
+    let by_height = 1.2 * radius;
+    let by_width = 1.6 * radius / (0.6 * digits.max(1) as f32);
+    by_height.min(by_width)
+}
+
+/// Font size of labels drawn beside dots.
+pub const LABEL_FONT_SIZE: f32 = 14.0;
+/// Gap between a dot and its label, in pixels.
+pub const LABEL_GAP: f32 = 2.0;
+
+/// Font size of edge weights written with `RenderConfig::show_weights`.
+pub const WEIGHT_FONT_SIZE: f32 = 12.0;
+
+/// Edge weight written over its line, with a box in the background color behind it.
+#[derive(Debug, Clone, PartialEq)]
+struct WeightLabel {
+    text: String,
+    /// Center of the text, in pixels.
+    at: Coord,
+    box_from: Coord,
+    box_to: Coord,
+}
+
+impl WeightLabel {
+    /// Label centered at `at`, the box assumes glyphs about 0.6 of the font size wide.
+    fn new(weight: f32, at: Coord) -> Self {
+        let text = weight.to_string();
+        let half_width = 0.3 * WEIGHT_FONT_SIZE * text.len() as f32 + LABEL_GAP;
+        let half_height = 0.5 * WEIGHT_FONT_SIZE + LABEL_GAP;
+        Self {
+            text,
+            at,
+            box_from: Coord::new(at.x - half_width, at.y - half_height),
+            box_to: Coord::new(at.x + half_width, at.y + half_height),
+        }
+    }
+}
+
+/// How much of each element is drawn, coarse drawing keeps huge graphs responsive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailLevel {
+    Full,
+    /// Dots are specks without labels, edges of the same style are drawn as one path.
+    Coarse,
+}
+
+/// Dots per screen above which drawing switches to `DetailLevel::Coarse`.
+pub const COARSE_DOT_THRESHOLD: f32 = 500.0;
+pub const COARSE_DOT_RADIUS: f32 = 1.5;
+
+/// Detail to draw `dot_count` dots with at the given zoom, where 1.0 shows the whole graph.
+///
+/// Zooming in by `zoom` leaves about `1 / zoom²` of the dots on screen.
+pub fn detail_level(dot_count: usize, zoom: f32) -> DetailLevel {
+    let visible = dot_count as f32 / (zoom * zoom).max(f32::EPSILON);
+    if visible > COARSE_DOT_THRESHOLD {
+        DetailLevel::Coarse
+    } else {
+        DetailLevel::Full
+    }
 }
 
 #[derive(Debug, Default)]
 pub struct DrawingApi {
     holder: GraphicsHolder,
+    edge_style: EdgeStyle,
+    config: RenderConfig,
+    /// Own RNG for `draw_dot`, the shared one from `crate::random` is used without it.
+    rng: Option<StdRng>,
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum DrawBackend {
     Egui,
     Iced,
+    /// Writes a PNG file to `RenderConfig::output` without opening a window.
+    Png,
+    /// Writes an SVG file next to `RenderConfig::output`, with an `.svg` extension.
+    Svg,
 }
 
 impl clap::ValueEnum for DrawBackend {
     fn value_variants<'a>() -> &'a [Self] {
-        &[DrawBackend::Egui, DrawBackend::Iced]
+        &[
+            DrawBackend::Egui,
+            DrawBackend::Iced,
+            DrawBackend::Png,
+            DrawBackend::Svg,
+        ]
     }
 
     fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
         Some(clap::builder::PossibleValue::new(match self {
             DrawBackend::Egui => "egui",
             DrawBackend::Iced => "iced",
+            DrawBackend::Png => "png",
+            DrawBackend::Svg => "svg",
         }))
     }
 }
 
 impl DrawingApi {
-    pub fn draw_dot(&mut self) -> Coord {
-        let mut gen = rand::thread_rng();
-        let x = gen.gen_range(0.0..1.0);
-        let y = gen.gen_range(0.0..1.0);
-        let coord = Coord { x, y };
+    pub fn new(config: &RenderConfig) -> Self {
+        Self {
+            holder: GraphicsHolder::new(config),
+            edge_style: config.edge_style,
+            config: config.clone(),
+            rng: None,
+        }
+    }
 
+    /// Api whose `draw_dot` places dots the same way for the same seed.
+    pub fn with_seed(config: &RenderConfig, seed: u64) -> Self {
+        Self {
+            rng: Some(StdRng::seed_from_u64(seed)),
+            ..Self::new(config)
+        }
+    }
+
+    /// Draws a dot at a random position and returns its index, see `draw_labeled_dot_at`.
+    pub fn draw_dot(&mut self) -> usize {
+        let place = |gen: &mut dyn RngCore| (gen.gen_range(0.0..1.0), gen.gen_range(0.0..1.0));
+        let (x, y) = match &mut self.rng {
+            Some(rng) => place(rng),
+            None => crate::random::with_rng(place),
+        };
+        self.draw_dot_at(Coord { x, y })
+    }
+
+    /// Draws a dot labeled with its index, see `draw_labeled_dot_at`.
+    pub fn draw_dot_at(&mut self, coord: Coord) -> usize {
+        let idx = self.holder.dots.len();
+        self.draw_labeled_dot_at(coord, idx.to_string())
+    }
+
+    /// Draws a dot and returns its index, which edges are drawn between.
+    ///
+    /// Dots get indices in the order they're drawn, starting at 0.
+    pub fn draw_labeled_dot_at(&mut self, coord: Coord, label: impl Into<String>) -> usize {
         self.holder.dots.push(coord);
-        coord
+        self.holder.dot_colors.push(self.holder.dot_style.color);
+        self.holder.labels.push(label.into());
+        self.holder.dots.len() - 1
+    }
+
+    /// Draws dot `idx` with `radius` instead of the dot style radius, does nothing without the dot.
+    pub fn size_dot(&mut self, idx: usize, radius: f32) {
+        if idx >= self.holder.dots.len() {
+            return;
+        }
+        let base = self.holder.dot_style.radius;
+        let radii = &mut self.holder.dot_radii;
+        if radii.len() <= idx {
+            radii.resize(idx + 1, base);
+        }
+        radii[idx] = radius;
+    }
+
+    /// Fills dot `idx` with `color` instead of the dot style color, does nothing without the dot.
+    pub fn color_dot(&mut self, idx: usize, color: Color) {
+        if let Some(dot_color) = self.holder.dot_colors.get_mut(idx) {
+            *dot_color = color;
+        }
+    }
+
+    /// Draws an edge between the dots with indices `from` and `to`,
+    /// does nothing unless both dots are drawn.
+    pub fn draw_edge(&mut self, from: usize, to: usize) {
+        self.draw_styled_edge(from, to, self.edge_style);
+    }
+
+    /// Same as `draw_edge` with `style` instead of the edge style.
+    pub fn draw_styled_edge(&mut self, from: usize, to: usize, style: EdgeStyle) {
+        self.push_line(from, to, style, None);
+    }
+
+    /// Edge drawn thicker the heavier it is compared to the other weighted edges.
+    pub fn draw_weighted_edge(&mut self, from: usize, to: usize, weight: f32) {
+        self.push_line(from, to, self.edge_style, Some(weight));
+    }
+
+    fn push_line(&mut self, from: usize, to: usize, style: EdgeStyle, weight: Option<f32>) {
+        let count = self.holder.dots.len();
+        if from >= count || to >= count {
+            return;
+        }
+        self.holder.lines.push(Line {
+            from,
+            to,
+            style,
+            weight,
+            bend: 0.0,
+        });
     }
 
-    pub fn draw_edge(&mut self, from: Coord, to: Coord) {
-        self.holder.lines.push((from, to));
+    /// Bows the most recently drawn edge by `bend` pixels to its left, see `parallel_bends`.
+    pub fn bend_last_edge(&mut self, bend: f32) {
+        if let Some(line) = self.holder.lines.last_mut() {
+            line.bend = bend;
+        }
+    }
+
+    pub fn into_holder(mut self) -> GraphicsHolder {
+        self.holder.apply_weights();
+        self.holder
+    }
+
+    /// Shows or exports the picture, see `draw_holder`.
+    pub fn draw_with(self, backend_type: DrawBackend) -> Result<DrawOutcome, DrawError> {
+        let config = self.config.clone();
+        draw_holder(self.into_holder(), &config, backend_type)
     }
+}
 
-    pub fn draw_with(self, backend_type: DrawBackend) {
-        match backend_type {
-            DrawBackend::Egui => eframe::run_native(
+/// Shows or exports `holder`, a window backend that fails to start falls back
+/// to `RenderConfig::fallback`.
+pub fn draw_holder(
+    holder: GraphicsHolder,
+    config: &RenderConfig,
+    backend_type: DrawBackend,
+) -> Result<DrawOutcome, DrawError> {
+    let for_window = holder.clone();
+    let removed_edges = Rc::new(RefCell::new(vec![]));
+    let result = match backend_type {
+        DrawBackend::Egui => open_window(backend_type, || {
+            let removed_edges = removed_edges.clone();
+            eframe::run_native(
                 "Graph draw egui",
                 eframe::NativeOptions::default(),
-                Box::new(|_| Box::new(egui_backend::DrawBackend::new(self.holder))),
+                Box::new(|_| Box::new(egui_backend::DrawBackend::new(for_window, removed_edges))),
             )
-            .unwrap_or_else(|err| eprintln!("Egui backend failed with {err}")),
-            DrawBackend::Iced => {
-                iced_backend::DrawBackend::run(iced::Settings::with_flags(self.holder))
-                    .unwrap_or_else(|err| eprintln!("Iced backend failed with {err}"))
+            .map_err(|err| err.to_string())
+        }),
+        DrawBackend::Iced => open_window(backend_type, || {
+            iced_backend::DrawBackend::run(iced::Settings::with_flags(for_window))
+                .map_err(|err| err.to_string())
+        }),
+        DrawBackend::Png => {
+            let path = config.output.clone();
+            return match render_to_png(&holder, &path, config) {
+                Ok(()) => Ok(DrawOutcome::Exported { path }),
+                Err(source) => Err(DrawError::Export { path, source }),
+            };
+        }
+        DrawBackend::Svg => {
+            let path = config.output.with_extension("svg");
+            let svg = holder.to_svg(config.width, config.height);
+            return match std::fs::write(&path, svg) {
+                Ok(()) => Ok(DrawOutcome::Exported { path }),
+                Err(source) => Err(DrawError::Export { path, source }),
+            };
+        }
+    };
+
+    match result {
+        Ok(()) => Ok(DrawOutcome::Shown {
+            removed_edges: removed_edges.take(),
+        }),
+        Err(err) => fall_back(&holder, config, err),
+    }
+}
+
+/// How a graph ended up drawn.
+#[derive(Debug)]
+pub enum DrawOutcome {
+    /// A window showed the graph until it was closed.
+    ///
+    /// `removed_edges` are the dots of every line removed in the window and not brought back,
+    /// in the order they were removed. Dots are numbered like the nodes of the graph drawn.
+    Shown { removed_edges: Vec<(usize, usize)> },
+    /// The picture was written to `path`.
+    Exported { path: PathBuf },
+    /// A window backend failed with `cause` and the picture was written to `path` instead.
+    FellBack { path: PathBuf, cause: DrawError },
+}
+
+/// Why a graph couldn't be drawn.
+#[derive(Debug)]
+pub enum DrawError {
+    /// A window backend failed to start or stopped with an error.
+    BackendInit {
+        backend: DrawBackend,
+        message: String,
+    },
+    /// Writing the picture to `path` failed.
+    Export { path: PathBuf, source: io::Error },
+    /// The render config refers to a node the graph doesn't have.
+    NodeIndexOutOfRange { node: usize, dot_count: usize },
+    /// `Layout::Fixed` doesn't have exactly one position per node.
+    PositionCountMismatch { positions: usize, dot_count: usize },
+}
+
+impl fmt::Display for DrawError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DrawError::BackendInit { backend, message } => {
+                write!(f, "{backend:?} backend failed: {message}")
+            }
+            DrawError::Export { path, source } => {
+                write!(
+                    f,
+                    "failed to save the graph to {}: {source}",
+                    path.display()
+                )
+            }
+            DrawError::NodeIndexOutOfRange { node, dot_count } => {
+                write!(
+                    f,
+                    "node {node} is out of range, the graph has {dot_count} nodes"
+                )
+            }
+            DrawError::PositionCountMismatch {
+                positions,
+                dot_count,
+            } => {
+                write!(
+                    f,
+                    "got {positions} node positions, the graph has {dot_count} nodes"
+                )
             }
         }
     }
 }
 
+impl std::error::Error for DrawError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DrawError::Export { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+fn open_window(
+    backend: DrawBackend,
+    run: impl FnOnce() -> Result<(), String>,
+) -> Result<(), DrawError> {
+    // winit panics instead of returning an error when there's no display to connect to.
+    let message = match panic::catch_unwind(AssertUnwindSafe(run)) {
+        Ok(result) => result.err(),
+        Err(_) => Some("failed to start".to_owned()),
+    };
+    match message {
+        Some(message) => Err(DrawError::BackendInit { backend, message }),
+        None => Ok(()),
+    }
+}
+
+/// Exports to `config.fallback` after a window backend failed with `err`.
+///
+/// Gives `DrawOutcome::FellBack` with `err` once the fallback is written, so callers can
+/// tell no window was shown. Without a fallback `err` is returned as is.
+pub fn fall_back(
+    holder: &GraphicsHolder,
+    config: &RenderConfig,
+    err: DrawError,
+) -> Result<DrawOutcome, DrawError> {
+    let Some(path) = config.fallback.clone() else {
+        return Err(err);
+    };
+    match render_to_png(holder, &path, config) {
+        Ok(()) => Ok(DrawOutcome::FellBack { path, cause: err }),
+        Err(source) => Err(DrawError::Export { path, source }),
+    }
+}
+
+pub use png_backend::{export_frames, render_to_png};
+
 pub mod iced_backend {
     impl Mul<iced::Size> for Coord {
         type Output = iced::Point;
@@ -86,13 +1070,45 @@ pub mod iced_backend {
     }
 
     #[derive(Debug, Clone, PartialEq)]
-    pub(super) struct Message;
+    pub(super) enum Message {
+        /// Show or hide node indices next to the dots, bound to the `L` key.
+        ToggleLabels,
+        /// Show or hide every line, bound to the `E` key.
+        ToggleEdges,
+        /// Show or hide dots without lines, bound to the `I` key.
+        ToggleIsolated,
+        /// Dot with the given index was dragged to a new place.
+        MoveDot(usize, Coord),
+        /// Time to show the next line of an animation.
+        RevealLine,
+    }
 
     use std::{fmt::Debug, ops::Mul};
 
-    use iced::{widget::canvas, Application};
+    use iced::{event, keyboard, mouse, widget::canvas, Application};
+
+    use super::{
+        detail_level, inside_font_size, legend_rows, loop_circle, Color, Coord, DetailLevel,
+        GraphicsHolder, COARSE_DOT_RADIUS, LABEL_FONT_SIZE, LABEL_GAP, WEIGHT_FONT_SIZE,
+    };
+
+    impl From<Coord> for iced::Point {
+        fn from(coord: Coord) -> Self {
+            iced::Point::new(coord.x, coord.y)
+        }
+    }
+
+    impl From<iced::Point> for Coord {
+        fn from(point: iced::Point) -> Self {
+            Coord::new(point.x, point.y)
+        }
+    }
 
-    use super::{Coord, GraphicsHolder};
+    impl From<Color> for iced::Color {
+        fn from(color: Color) -> Self {
+            iced::Color::from_rgba8(color.r, color.g, color.b, color.a as f32 / 255.0)
+        }
+    }
 
     impl Application for DrawBackend {
         type Message = Message;
@@ -100,11 +1116,15 @@ pub mod iced_backend {
         type Theme = iced::Theme;
         type Flags = GraphicsHolder;
 
-        fn new(graph: Self::Flags) -> (Self, iced::Command<Self::Message>) {
+        fn new(mut graph: Self::Flags) -> (Self, iced::Command<Self::Message>) {
+            graph.start_animation();
             (
                 Self {
                     canvas_drawer: CanvasDrawer {
                         holder: graph,
+                        show_labels: true,
+                        show_edges: true,
+                        show_isolated: true,
                         ..Default::default()
                     },
                 },
@@ -116,10 +1136,39 @@ pub mod iced_backend {
             "iced-based graphs".to_string()
         }
 
-        fn update(&mut self, _message: Self::Message) -> iced::Command<Message> {
+        fn update(&mut self, message: Self::Message) -> iced::Command<Message> {
+            match message {
+                Message::ToggleLabels => self.canvas_drawer.toggle_labels(),
+                Message::ToggleEdges => self.canvas_drawer.toggle_edges(),
+                Message::ToggleIsolated => self.canvas_drawer.toggle_isolated(),
+                Message::MoveDot(idx, to) => self.canvas_drawer.move_dot(idx, to),
+                Message::RevealLine => self.canvas_drawer.reveal_line(),
+            }
             iced::Command::none()
         }
 
+        fn subscription(&self) -> iced::Subscription<Self::Message> {
+            let keys = iced::subscription::events_with(|event, status| match (event, status) {
+                (
+                    iced::Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. }),
+                    event::Status::Ignored,
+                ) => match key_code {
+                    keyboard::KeyCode::L => Some(Message::ToggleLabels),
+                    keyboard::KeyCode::E => Some(Message::ToggleEdges),
+                    keyboard::KeyCode::I => Some(Message::ToggleIsolated),
+                    _ => None,
+                },
+                _ => None,
+            });
+
+            // Dropped once every line is shown.
+            let reveal = match self.canvas_drawer.holder.next_reveal() {
+                Some(delay) => iced::time::every(delay).map(|_| Message::RevealLine),
+                None => iced::Subscription::none(),
+            };
+            iced::Subscription::batch([keys, reveal])
+        }
+
         fn view(&self) -> iced::Element<'_, Self::Message> {
             iced::widget::column!(iced::widget::canvas(&self.canvas_drawer)
                 .width(iced::Length::Fill)
@@ -130,49 +1179,385 @@ pub mod iced_backend {
         }
     }
 
+    /// Zoom factor per scroll wheel line.
+    const ZOOM_PER_SCROLL_STEP: f32 = 1.15;
+    /// Touchpad scroll distance counted as one wheel line.
+    const PIXELS_PER_SCROLL_STEP: f32 = 40.0;
+    const MIN_ZOOM: f32 = 0.2;
+    const MAX_ZOOM: f32 = 50.0;
+
+    /// Pan and zoom applied on top of the canvas, dots and lines keep their on-screen size.
+    #[derive(Debug, Clone, Copy)]
+    pub(super) struct View {
+        offset: iced::Vector,
+        scale: f32,
+    }
+
+    impl Default for View {
+        fn default() -> Self {
+            Self {
+                offset: iced::Vector::new(0.0, 0.0),
+                scale: 1.0,
+            }
+        }
+    }
+
+    impl View {
+        fn project(self, point: iced::Point) -> iced::Point {
+            iced::Point::new(
+                point.x * self.scale + self.offset.x,
+                point.y * self.scale + self.offset.y,
+            )
+        }
+
+        fn unproject(self, point: iced::Point) -> Coord {
+            Coord::new(
+                (point.x - self.offset.x) / self.scale,
+                (point.y - self.offset.y) / self.scale,
+            )
+        }
+
+        /// Zooms by `factor` keeping the canvas point under `cursor` in place.
+        fn zoom_at(&mut self, cursor: iced::Point, factor: f32) {
+            let anchor = self.unproject(cursor);
+            self.scale = (self.scale * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+            self.offset = iced::Vector::new(
+                cursor.x - anchor.x * self.scale,
+                cursor.y - anchor.y * self.scale,
+            );
+        }
+    }
+
+    /// Mouse state of the canvas between events.
+    #[derive(Debug, Default)]
+    pub(super) struct Interaction {
+        view: View,
+        /// Dot being dragged.
+        dragged: Option<usize>,
+        /// Last cursor position while dragging empty space to pan.
+        panned_from: Option<iced::Point>,
+    }
+
     #[derive(Debug, Default)]
     struct CanvasDrawer {
         cache: canvas::Cache,
         holder: GraphicsHolder,
+        show_labels: bool,
+        show_edges: bool,
+        /// Whether dots without any lines are drawn.
+        show_isolated: bool,
+    }
+
+    impl CanvasDrawer {
+        fn toggle_labels(&mut self) {
+            self.show_labels = !self.show_labels;
+            self.cache.clear();
+        }
+
+        fn toggle_edges(&mut self) {
+            self.show_edges = !self.show_edges;
+            self.cache.clear();
+        }
+
+        fn toggle_isolated(&mut self) {
+            self.show_isolated = !self.show_isolated;
+            self.cache.clear();
+        }
+
+        fn move_dot(&mut self, idx: usize, to: Coord) {
+            self.holder.move_dot(idx, to);
+            self.cache.clear();
+        }
+
+        fn reveal_line(&mut self) {
+            if self.holder.reveal_line() {
+                self.cache.clear();
+            }
+        }
     }
 
     impl canvas::Program<Message> for CanvasDrawer {
-        type State = GraphicsHolder;
+        type State = Interaction;
+
+        fn update(
+            &self,
+            state: &mut Self::State,
+            event: canvas::Event,
+            bounds: iced::Rectangle,
+            cursor: canvas::Cursor,
+        ) -> (event::Status, Option<Message>) {
+            let canvas::Event::Mouse(event) = event else {
+                return (event::Status::Ignored, None);
+            };
+            let view = state.view;
+
+            match event {
+                mouse::Event::ButtonPressed(mouse::Button::Left) => {
+                    let Some(point) = cursor.position_in(&bounds) else {
+                        return (event::Status::Ignored, None);
+                    };
+                    let unzoomed = view.unproject(point);
+                    let (width, height) = (bounds.width, bounds.height);
+                    state.dragged = self.holder.dot_at(unzoomed, width, height, view.scale);
+                    if state.dragged.is_none() {
+                        state.panned_from = Some(point);
+                    }
+                    (event::Status::Captured, None)
+                }
+                mouse::Event::CursorMoved { .. } => {
+                    let Some(point) = cursor.position_from(bounds.position()) else {
+                        return (event::Status::Ignored, None);
+                    };
+                    if let Some(idx) = state.dragged {
+                        let unzoomed = view.unproject(point);
+                        let to = Coord::new(
+                            (unzoomed.x / bounds.width).clamp(0.0, 1.0),
+                            (unzoomed.y / bounds.height).clamp(0.0, 1.0),
+                        );
+                        return (event::Status::Captured, Some(Message::MoveDot(idx, to)));
+                    }
+                    let Some(from) = state.panned_from.replace(point) else {
+                        return (event::Status::Ignored, None);
+                    };
+                    state.view.offset = state.view.offset + (point - from);
+                    self.cache.clear();
+                    (event::Status::Captured, None)
+                }
+                mouse::Event::ButtonReleased(mouse::Button::Left) => {
+                    let dragged = state.dragged.take().is_some();
+                    let panned = state.panned_from.take().is_some();
+                    if dragged || panned {
+                        (event::Status::Captured, None)
+                    } else {
+                        (event::Status::Ignored, None)
+                    }
+                }
+                mouse::Event::WheelScrolled { delta } => {
+                    let Some(point) = cursor.position_in(&bounds) else {
+                        return (event::Status::Ignored, None);
+                    };
+                    let steps = match delta {
+                        mouse::ScrollDelta::Lines { y, .. } => y,
+                        mouse::ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_SCROLL_STEP,
+                    };
+                    state.view.zoom_at(point, ZOOM_PER_SCROLL_STEP.powf(steps));
+                    self.cache.clear();
+                    (event::Status::Captured, None)
+                }
+                _ => (event::Status::Ignored, None),
+            }
+        }
 
         fn draw(
             &self,
-            _state: &Self::State,
+            state: &Self::State,
             _theme: &iced::Theme,
             bounds: iced::Rectangle,
             _cursor: canvas::Cursor,
         ) -> Vec<canvas::Geometry> {
+            let view = state.view;
             let geom = self.cache.draw(bounds.size(), |frame| {
+                let size = frame.size();
+                let at = |coord: Coord| view.project(coord * size);
                 frame.fill(
                     &canvas::Path::rectangle(iced::Point::new(0.0, 0.0), frame.size()),
-                    iced::Color::from_rgb8(0x20, 0x20, 0x20),
+                    iced::Color::from(self.holder.background),
                 );
 
-                let white = iced::Color::from_rgb8(0xff, 0xff, 0xff);
-                for dot in self.holder.dots.iter().copied() {
-                    let dot_form = canvas::Path::circle(dot * frame.size(), 5.0);
-                    frame.fill(&dot_form, white)
+                let dot_style = self.holder.dot_style;
+                let detail = detail_level(self.holder.dots.len(), view.scale);
+                let radius = |idx| match detail {
+                    DetailLevel::Full => self.holder.dot_radius(idx),
+                    DetailLevel::Coarse => COARSE_DOT_RADIUS,
+                };
+                // Isolated dots are the ones with no line ends attached.
+                let degrees = if self.show_isolated {
+                    vec![]
+                } else {
+                    self.holder.dot_degrees()
+                };
+                let shown = |idx: &usize| !matches!(degrees.get(*idx), Some(0));
+
+                let dots = self.holder.dots.iter().zip(self.holder.dot_colors.iter());
+                for (idx, (dot, color)) in dots.enumerate().filter(|(idx, _)| shown(idx)) {
+                    let dot_form = canvas::Path::circle(at(*dot), radius(idx));
+                    frame.fill(&dot_form, iced::Color::from(*color))
+                }
+
+                if self.show_labels && detail == DetailLevel::Full {
+                    let labels = self.holder.dots.iter().zip(self.holder.labels.iter());
+                    for (idx, (dot, label)) in labels.enumerate().filter(|(idx, _)| shown(idx)) {
+                        let shift = self.holder.dot_radius(idx) + LABEL_GAP;
+                        let offset = iced::Vector::new(shift, shift);
+                        frame.fill_text(canvas::Text {
+                            content: label.clone(),
+                            position: at(*dot) + offset,
+                            color: dot_style.color.into(),
+                            size: LABEL_FONT_SIZE,
+                            ..Default::default()
+                        })
+                    }
+                }
+
+                match detail {
+                    _ if !self.show_edges => {}
+                    DetailLevel::Full => {
+                        for line in self.holder.lines.iter() {
+                            let (from, to) = self.holder.line_ends(line);
+                            let (from, to) = (Coord::from(at(from)), Coord::from(at(to)));
+                            let path = canvas::Path::new(|builder| {
+                                if line.is_loop() {
+                                    let radius = self.holder.dot_radius(line.to);
+                                    let (center, radius) = loop_circle(from, radius);
+                                    builder.circle(center.into(), radius);
+                                    return;
+                                }
+                                builder.move_to(from.into());
+                                match line.control(from, to) {
+                                    Some(control) => {
+                                        builder.quadratic_curve_to(control.into(), to.into())
+                                    }
+                                    None => builder.line_to(to.into()),
+                                }
+                                let arrowhead = self.holder.arrowhead(line, from, to);
+                                if let Some((tip, [left, right])) = arrowhead {
+                                    builder.move_to(left.into());
+                                    builder.line_to(tip.into());
+                                    builder.line_to(right.into());
+                                }
+                            });
+                            let stroke = canvas::Stroke::default()
+                                .with_color(line.style.color.into())
+                                .with_width(line.style.width);
+                            frame.stroke(&path, stroke)
+                        }
+                    }
+                    DetailLevel::Coarse => {
+                        for run in self.holder.style_runs() {
+                            let path = canvas::Path::new(|builder| {
+                                for line in run {
+                                    let (from, to) = self.holder.line_ends(line);
+                                    builder.move_to(at(from));
+                                    builder.line_to(at(to));
+                                }
+                            });
+                            let stroke = canvas::Stroke::default()
+                                .with_color(run[0].style.color.into())
+                                .with_width(run[0].style.width);
+                            frame.stroke(&path, stroke)
+                        }
+                    }
+                }
+
+                if self.show_edges && detail == DetailLevel::Full {
+                    for line in self.holder.lines.iter() {
+                        let (from, to) = self.holder.line_ends(line);
+                        let (from, to) = (Coord::from(at(from)), Coord::from(at(to)));
+                        let Some(label) = self.holder.weight_label(line, from, to) else {
+                            continue;
+                        };
+                        let (corner, end) = (label.box_from, label.box_to);
+                        frame.fill_rectangle(
+                            corner.into(),
+                            iced::Size::new(end.x - corner.x, end.y - corner.y),
+                            iced::Color::from(self.holder.background),
+                        );
+                        frame.fill_text(canvas::Text {
+                            content: label.text,
+                            position: label.at.into(),
+                            color: dot_style.color.into(),
+                            size: WEIGHT_FONT_SIZE,
+                            horizontal_alignment: iced::alignment::Horizontal::Center,
+                            vertical_alignment: iced::alignment::Vertical::Center,
+                            ..Default::default()
+                        })
+                    }
+                }
+
+                if self.holder.ids_inside && detail == DetailLevel::Full {
+                    let dots = self.holder.dots.iter().copied().enumerate();
+                    for (idx, dot) in dots.filter(|(idx, _)| shown(idx)) {
+                        let content = idx.to_string();
+                        frame.fill_text(canvas::Text {
+                            size: inside_font_size(self.holder.dot_radius(idx), content.len()),
+                            content,
+                            position: at(dot),
+                            color: self.holder.background.into(),
+                            horizontal_alignment: iced::alignment::Horizontal::Center,
+                            vertical_alignment: iced::alignment::Vertical::Center,
+                            ..Default::default()
+                        })
+                    }
                 }
 
-                for (from, to) in self.holder.lines.iter().copied() {
-                    let line = canvas::Path::line(from * frame.size(), to * frame.size());
-                    frame.stroke(&line, canvas::Stroke::default().with_color(white))
+                for row in legend_rows(&self.holder.legend, frame.height()) {
+                    let sample = canvas::Path::line(row.from.into(), row.to.into());
+                    let stroke = canvas::Stroke::default()
+                        .with_color(dot_style.color.into())
+                        .with_width(row.width);
+                    frame.stroke(&sample, stroke);
+                    frame.fill_text(canvas::Text {
+                        content: row.label,
+                        position: row.label_at.into(),
+                        color: dot_style.color.into(),
+                        size: LABEL_FONT_SIZE,
+                        vertical_alignment: iced::alignment::Vertical::Center,
+                        ..Default::default()
+                    })
                 }
             });
 
             vec![geom]
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn toggling_labels_twice_restores_them() {
+            let (mut backend, _) = DrawBackend::new(GraphicsHolder::default());
+            assert!(backend.canvas_drawer.show_labels);
+
+            let _ = backend.update(Message::ToggleLabels);
+            assert!(!backend.canvas_drawer.show_labels);
+            let _ = backend.update(Message::ToggleLabels);
+            assert!(backend.canvas_drawer.show_labels);
+        }
+    }
 }
 
 mod egui_backend {
-    use std::ops::Mul;
+    use std::{
+        cell::RefCell,
+        ops::Mul,
+        path::PathBuf,
+        rc::Rc,
+        time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+    };
+
+    use super::{
+        detail_level, inside_font_size, legend_rows, loop_circle, png_backend::save_png, Color,
+        Coord, DetailLevel, GraphicsHolder, Line, COARSE_DOT_RADIUS, LABEL_FONT_SIZE, LABEL_GAP,
+        WEIGHT_FONT_SIZE,
+    };
+
+    const EDGE_HIT_DISTANCE: f32 = 4.0;
+    /// How long the outcome of a screenshot stays on screen.
+    const NOTICE_DURATION: Duration = Duration::from_secs(2);
+
+    impl From<Color> for egui::Color32 {
+        fn from(color: Color) -> Self {
+            egui::Color32::from_rgba_unmultiplied(color.r, color.g, color.b, color.a)
+        }
+    }
 
-    use super::{Coord, GraphicsHolder};
+    impl From<Coord> for egui::Pos2 {
+        fn from(coord: Coord) -> Self {
+            egui::Pos2::new(coord.x, coord.y)
+        }
+    }
 
     impl Mul<egui::Vec2> for Coord {
         type Output = egui::Pos2;
@@ -188,27 +1573,313 @@ mod egui_backend {
     #[derive(Debug, Default)]
     pub(super) struct DrawBackend {
         graph: GraphicsHolder,
+        /// Lines removed with a right click along with their former positions, latest last.
+        removed: Vec<(usize, Line)>,
+        /// Dots of the lines in `removed`, read by `draw_holder` once the window is closed.
+        removed_edges: Rc<RefCell<Vec<(usize, usize)>>>,
+        /// When the last line of an animation was revealed, or it started.
+        revealed_at: Option<Instant>,
+        /// Outcome of the latest screenshot and when it was taken.
+        notice: Option<(String, Instant)>,
+        show_edges: bool,
+        /// Whether dots without any lines are drawn.
+        show_isolated: bool,
     }
 
     impl DrawBackend {
-        pub(super) fn new(graph: GraphicsHolder) -> Self {
-            DrawBackend { graph }
+        pub(super) fn new(
+            mut graph: GraphicsHolder,
+            removed_edges: Rc<RefCell<Vec<(usize, usize)>>>,
+        ) -> Self {
+            graph.start_animation();
+            DrawBackend {
+                graph,
+                removed: vec![],
+                removed_edges,
+                revealed_at: None,
+                notice: None,
+                show_edges: true,
+                show_isolated: true,
+            }
+        }
+
+        /// `E` shows or hides every line, `I` dots without lines.
+        fn handle_toggles(&mut self, ui: &egui::Ui) {
+            let (edges, isolated) = ui.input(|input| {
+                (
+                    input.key_pressed(egui::Key::E),
+                    input.key_pressed(egui::Key::I),
+                )
+            });
+            self.show_edges ^= edges;
+            self.show_isolated ^= isolated;
+        }
+
+        /// `S` saves what's on screen to `graph_<unix time>.png` in the working directory.
+        ///
+        /// The picture goes through the PNG renderer at the window size, so it has the dots
+        /// and lines the toggles leave shown but no text.
+        fn handle_screenshot(&mut self, ui: &egui::Ui) {
+            if !ui.input(|input| input.key_pressed(egui::Key::S)) {
+                return;
+            }
+
+            let secs = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since| since.as_secs());
+            let path = PathBuf::from(format!("graph_{secs}.png"));
+            let size = ui.available_size();
+            let (width, height) = (size.x.round() as u32, size.y.round() as u32);
+            let shown = self.graph.with_toggles(self.show_edges, self.show_isolated);
+            let text = match save_png(&shown, &path, width, height) {
+                Ok(()) => format!("Saved {}", path.display()),
+                Err(err) => format!("Couldn't save {}: {err}", path.display()),
+            };
+            self.notice = Some((text, Instant::now()));
+        }
+
+        /// Shows the screenshot notice in the bottom left corner until it expires.
+        fn show_notice(&mut self, ui: &egui::Ui) {
+            let Some((text, shown_at)) = &self.notice else {
+                return;
+            };
+            let Some(left) = NOTICE_DURATION.checked_sub(shown_at.elapsed()) else {
+                self.notice = None;
+                return;
+            };
+
+            ui.painter().text(
+                egui::pos2(LABEL_GAP, ui.available_size().y - LABEL_GAP),
+                egui::Align2::LEFT_BOTTOM,
+                text,
+                egui::FontId::proportional(LABEL_FONT_SIZE),
+                self.graph.dot_style.color.into(),
+            );
+            ui.ctx().request_repaint_after(left);
+        }
+
+        /// Reveals the next line of an animation once its delay has passed
+        /// and asks for a repaint when the one after it is due.
+        fn animate(&mut self, ctx: &egui::Context) {
+            let Some(delay) = self.graph.next_reveal() else {
+                return;
+            };
+            let now = Instant::now();
+            let revealed_at = *self.revealed_at.get_or_insert(now);
+            if now - revealed_at >= delay {
+                self.graph.reveal_line();
+                self.revealed_at = Some(now);
+            }
+            if let Some(delay) = self.graph.next_reveal() {
+                ctx.request_repaint_after(delay);
+            }
         }
 
-        fn draw_once(&self, ctx: &egui::Context) {
-            egui::CentralPanel::default().show(ctx, |ui| {
+        /// Right click removes the closest line under the cursor unless it points at a dot,
+        /// Ctrl+Z brings the latest removed line back.
+        fn handle_edits(&mut self, ui: &egui::Ui) {
+            let size = ui.available_size();
+            let (clicked_at, undo) = ui.input(|input| {
+                let clicked_at = input
+                    .pointer
+                    .secondary_clicked()
+                    .then(|| input.pointer.interact_pos())
+                    .flatten();
+                (
+                    clicked_at,
+                    input.modifiers.command && input.key_pressed(egui::Key::Z),
+                )
+            });
+
+            if let Some(pos) = clicked_at {
+                let pos = Coord::new(pos.x, pos.y);
+                let on_dot = self.graph.dot_at(pos, size.x, size.y, 1.0).is_some();
+                let hit = self.graph.line_at(pos, size.x, size.y, EDGE_HIT_DISTANCE);
+                if let (false, Some(idx)) = (on_dot, hit) {
+                    let line = self.graph.lines.remove(idx);
+                    self.removed.push((idx, line));
+                }
+            }
+
+            if undo {
+                if let Some((idx, line)) = self.removed.pop() {
+                    self.graph.lines.insert(idx, line);
+                }
+            }
+
+            if clicked_at.is_some() || undo {
+                *self.removed_edges.borrow_mut() = self
+                    .removed
+                    .iter()
+                    .map(|(_, line)| (line.from, line.to))
+                    .collect();
+            }
+        }
+
+        /// Tooltip with the id and degree of the dot under the cursor, nothing elsewhere.
+        fn show_hovered(&self, ui: &egui::Ui) {
+            let size = ui.available_size();
+            let Some(pos) = ui.input(|input| input.pointer.hover_pos()) else {
+                return;
+            };
+            let Some(idx) = self
+                .graph
+                .dot_at(Coord::new(pos.x, pos.y), size.x, size.y, 1.0)
+            else {
+                return;
+            };
+
+            let degree = self.graph.dot_degree(idx);
+            egui::show_tooltip_at_pointer(ui.ctx(), egui::Id::new("hovered dot"), |ui| {
+                ui.label(format!("node {idx}\ndegree {degree}"));
+            });
+        }
+
+        fn draw_once(&mut self, ctx: &egui::Context) {
+            self.animate(ctx);
+            let panel = egui::Frame::central_panel(&ctx.style()).fill(self.graph.background.into());
+            egui::CentralPanel::default().frame(panel).show(ctx, |ui| {
+                self.handle_edits(ui);
+                self.handle_screenshot(ui);
+                self.handle_toggles(ui);
+
                 let painter = ui.painter();
-                let white = egui::Color32::from_rgb(0xff, 0xff, 0xff);
+                let dot_style = self.graph.dot_style;
+                let detail = detail_level(self.graph.dots.len(), 1.0);
+                let radius = |idx| match detail {
+                    DetailLevel::Full => self.graph.dot_radius(idx),
+                    DetailLevel::Coarse => COARSE_DOT_RADIUS,
+                };
+
+                // Isolated dots are the ones with no line ends attached.
+                let degrees = if self.show_isolated {
+                    vec![]
+                } else {
+                    self.graph.dot_degrees()
+                };
+                let shown = |idx: &usize| !matches!(degrees.get(*idx), Some(0));
+
+                let dots = self.graph.dots.iter().zip(self.graph.dot_colors.iter());
+                for (idx, (dot, color)) in dots.enumerate().filter(|(idx, _)| shown(idx)) {
+                    painter.circle_filled(*dot * ui.available_size(), radius(idx), *color);
+                }
+
+                if detail == DetailLevel::Full {
+                    let labels = self.graph.dots.iter().zip(self.graph.labels.iter());
+                    for (idx, (dot, label)) in labels.enumerate().filter(|(idx, _)| shown(idx)) {
+                        let shift = self.graph.dot_radius(idx) + LABEL_GAP;
+                        painter.text(
+                            *dot * ui.available_size() + egui::vec2(shift, shift),
+                            egui::Align2::LEFT_TOP,
+                            label,
+                            egui::FontId::proportional(LABEL_FONT_SIZE),
+                            dot_style.color.into(),
+                        );
+                    }
+                }
+
+                let size = ui.available_size();
+                let segment = |line: &Line| {
+                    let (from, to) = self.graph.line_ends(line);
+                    egui::Shape::line_segment(
+                        [from * size, to * size],
+                        (line.style.width, line.style.color),
+                    )
+                };
+                match detail {
+                    _ if !self.show_edges => {}
+                    DetailLevel::Full => {
+                        for line in self.graph.lines.iter() {
+                            let (from, to) = self.graph.line_ends(line);
+                            let from = from.scaled(size.x, size.y);
+                            let to = to.scaled(size.x, size.y);
+                            if line.is_loop() {
+                                let radius = self.graph.dot_radius(line.to);
+                                let (center, radius) = loop_circle(from, radius);
+                                let stroke = (line.style.width, line.style.color);
+                                painter.circle_stroke(center.into(), radius, stroke);
+                            } else {
+                                match line.control(from, to) {
+                                    Some(control) => {
+                                        painter.add(
+                                            egui::epaint::QuadraticBezierShape::from_points_stroke(
+                                                [from.into(), control.into(), to.into()],
+                                                false,
+                                                egui::Color32::TRANSPARENT,
+                                                (line.style.width, line.style.color),
+                                            ),
+                                        );
+                                    }
+                                    None => {
+                                        painter.add(segment(line));
+                                    }
+                                }
+                                if let Some((tip, wings)) = self.graph.arrowhead(line, from, to) {
+                                    let stroke = (line.style.width, line.style.color);
+                                    for wing in wings {
+                                        painter.line_segment([wing.into(), tip.into()], stroke);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    DetailLevel::Coarse => {
+                        painter.add(egui::Shape::Vec(
+                            self.graph.lines.iter().map(segment).collect(),
+                        ));
+                    }
+                }
 
-                for dot in self.graph.dots.iter().copied() {
-                    painter.circle_filled(dot * ui.available_size(), 5.0, white);
+                if self.show_edges && detail == DetailLevel::Full {
+                    for line in self.graph.lines.iter() {
+                        let (from, to) = self.graph.line_ends(line);
+                        let from = from.scaled(size.x, size.y);
+                        let to = to.scaled(size.x, size.y);
+                        let Some(label) = self.graph.weight_label(line, from, to) else {
+                            continue;
+                        };
+                        let rect =
+                            egui::Rect::from_two_pos(label.box_from.into(), label.box_to.into());
+                        painter.rect_filled(rect, 0.0, self.graph.background);
+                        painter.text(
+                            label.at.into(),
+                            egui::Align2::CENTER_CENTER,
+                            label.text,
+                            egui::FontId::proportional(WEIGHT_FONT_SIZE),
+                            dot_style.color.into(),
+                        );
+                    }
                 }
 
-                for (from, to) in self.graph.lines.iter().copied() {
-                    let from = from * ui.available_size();
-                    let to = to * ui.available_size();
-                    painter.line_segment([from, to], (1.0, white));
+                if self.graph.ids_inside && detail == DetailLevel::Full {
+                    let dots = self.graph.dots.iter().copied().enumerate();
+                    for (idx, dot) in dots.filter(|(idx, _)| shown(idx)) {
+                        let text = idx.to_string();
+                        let size = inside_font_size(self.graph.dot_radius(idx), text.len());
+                        painter.text(
+                            dot * ui.available_size(),
+                            egui::Align2::CENTER_CENTER,
+                            text,
+                            egui::FontId::proportional(size),
+                            self.graph.background.into(),
+                        );
+                    }
                 }
+
+                for row in legend_rows(&self.graph.legend, ui.available_size().y) {
+                    let color: egui::Color32 = dot_style.color.into();
+                    painter.line_segment([row.from.into(), row.to.into()], (row.width, color));
+                    painter.text(
+                        row.label_at.into(),
+                        egui::Align2::LEFT_CENTER,
+                        row.label,
+                        egui::FontId::proportional(LABEL_FONT_SIZE),
+                        color,
+                    );
+                }
+
+                self.show_hovered(ui);
+                self.show_notice(ui);
             });
         }
     }
@@ -219,3 +1890,611 @@ mod egui_backend {
         }
     }
 }
+
+mod png_backend {
+    use std::{io, path::Path};
+
+    use tiny_skia::{Color, FillRule, Paint, PathBuilder, Pixmap, Stroke, Transform};
+
+    use super::{loop_circle, Coord, GraphicsHolder, RenderConfig};
+
+    impl Coord {
+        fn on_pixmap(self, pixmap: &Pixmap) -> (f32, f32) {
+            (
+                self.x * pixmap.width() as f32,
+                self.y * pixmap.height() as f32,
+            )
+        }
+    }
+
+    fn paint(color: super::Color) -> Paint<'static> {
+        let mut paint = Paint::default();
+        paint.set_color_rgba8(color.r, color.g, color.b, color.a);
+        paint.anti_alias = true;
+        paint
+    }
+
+    fn render(holder: &GraphicsHolder, width: u32, height: u32) -> io::Result<Pixmap> {
+        let mut pixmap = Pixmap::new(width, height).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("can't create {width}x{height} image"),
+            )
+        })?;
+        let background = holder.background;
+        pixmap.fill(Color::from_rgba8(
+            background.r,
+            background.g,
+            background.b,
+            background.a,
+        ));
+
+        for line in holder.lines.iter() {
+            let (from, to) = holder.line_ends(line);
+            let path = if line.is_loop() {
+                let (x, y) = from.on_pixmap(&pixmap);
+                let (center, radius) = loop_circle(Coord::new(x, y), holder.dot_radius(line.to));
+                PathBuilder::from_circle(center.x, center.y, radius)
+            } else {
+                let mut path = PathBuilder::new();
+                let (x, y) = from.on_pixmap(&pixmap);
+                let from = Coord::new(x, y);
+                path.move_to(x, y);
+                let (x, y) = to.on_pixmap(&pixmap);
+                let to = Coord::new(x, y);
+                match line.control(from, to) {
+                    Some(control) => path.quad_to(control.x, control.y, x, y),
+                    None => path.line_to(x, y),
+                }
+                if let Some((tip, [left, right])) = holder.arrowhead(line, from, to) {
+                    path.move_to(left.x, left.y);
+                    path.line_to(tip.x, tip.y);
+                    path.line_to(right.x, right.y);
+                }
+                path.finish()
+            };
+
+            let stroke = Stroke {
+                width: line.style.width,
+                ..Default::default()
+            };
+
+            if let Some(path) = path {
+                let paint = paint(line.style.color);
+                pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+            }
+        }
+
+        let dots = holder.dots.iter().zip(holder.dot_colors.iter());
+        for (idx, (dot, color)) in dots.enumerate() {
+            let (x, y) = dot.on_pixmap(&pixmap);
+            if let Some(circle) = PathBuilder::from_circle(x, y, holder.dot_radius(idx)) {
+                pixmap.fill_path(
+                    &circle,
+                    &paint(*color),
+                    FillRule::Winding,
+                    Transform::identity(),
+                    None,
+                );
+            }
+        }
+
+        Ok(pixmap)
+    }
+
+    pub fn render_to_png(
+        holder: &GraphicsHolder,
+        path: &Path,
+        config: &RenderConfig,
+    ) -> io::Result<()> {
+        save_png(holder, path, config.width, config.height)
+    }
+
+    /// Writes the picture as a `width` x `height` PNG at `path`.
+    pub(super) fn save_png(
+        holder: &GraphicsHolder,
+        path: &Path,
+        width: u32,
+        height: u32,
+    ) -> io::Result<()> {
+        render(holder, width, height)?
+            .save_png(path)
+            .map_err(io::Error::other)
+    }
+
+    /// Renders every frame into `dir` as `{prefix}_000.png`, `{prefix}_001.png`, ...
+    ///
+    /// Indices are padded to at least three digits, more if there are enough frames to need it.
+    pub fn export_frames(
+        frames: &[GraphicsHolder],
+        dir: &Path,
+        prefix: &str,
+        config: &RenderConfig,
+    ) -> io::Result<()> {
+        let pad = frames.len().saturating_sub(1).to_string().len().max(3);
+
+        for (idx, frame) in frames.iter().enumerate() {
+            let path = dir.join(format!("{prefix}_{idx:0pad$}.png"));
+            render_to_png(frame, &path, config)?;
+        }
+        Ok(())
+    }
+}
+
+mod svg_backend {
+    use std::fmt::Write;
+
+    use super::{inside_font_size, loop_circle, Color, GraphicsHolder, WEIGHT_FONT_SIZE};
+
+    impl GraphicsHolder {
+        /// SVG document of the picture with a `width` x `height` viewport on a background `<rect>`.
+        ///
+        /// Straight edges are `<line>`s, bent ones `<path>`s and self-loops `<circle>`s,
+        /// arrowheads are `<polyline>`s. Every dot is a filled `<circle>` drawn over the edges,
+        /// weight labels and ids inside dots are `<text>`s, weight labels over a `<rect>`.
+        pub fn to_svg(&self, width: u32, height: u32) -> String {
+            let (w, h) = (width as f32, height as f32);
+            let mut svg = String::new();
+            let _ = writeln!(
+                svg,
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}">"#
+            );
+            let _ = writeln!(
+                svg,
+                r#"  <rect width="100%" height="100%" {}/>"#,
+                paint("fill", self.background)
+            );
+
+            for line in self.lines.iter() {
+                let (from, to) = self.line_ends(line);
+                let (from, to) = (from.scaled(w, h), to.scaled(w, h));
+                if line.is_loop() {
+                    let (center, radius) = loop_circle(from, self.dot_radius(line.to));
+                    let _ = writeln!(
+                        svg,
+                        r#"  <circle cx="{:.2}" cy="{:.2}" r="{radius}" fill="none" stroke-width="{}" {}/>"#,
+                        center.x,
+                        center.y,
+                        line.style.width,
+                        paint("stroke", line.style.color)
+                    );
+                    continue;
+                }
+                match line.control(from, to) {
+                    Some(control) => writeln!(
+                        svg,
+                        r#"  <path d="M {:.2} {:.2} Q {:.2} {:.2} {:.2} {:.2}" fill="none" stroke-width="{}" {}/>"#,
+                        from.x,
+                        from.y,
+                        control.x,
+                        control.y,
+                        to.x,
+                        to.y,
+                        line.style.width,
+                        paint("stroke", line.style.color)
+                    ),
+                    None => writeln!(
+                        svg,
+                        r#"  <line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke-width="{}" {}/>"#,
+                        from.x,
+                        from.y,
+                        to.x,
+                        to.y,
+                        line.style.width,
+                        paint("stroke", line.style.color)
+                    ),
+                }
+                .ok();
+                if let Some((tip, [left, right])) = self.arrowhead(line, from, to) {
+                    let _ = writeln!(
+                        svg,
+                        r#"  <polyline points="{:.2},{:.2} {:.2},{:.2} {:.2},{:.2}" fill="none" stroke-width="{}" {}/>"#,
+                        left.x,
+                        left.y,
+                        tip.x,
+                        tip.y,
+                        right.x,
+                        right.y,
+                        line.style.width,
+                        paint("stroke", line.style.color)
+                    );
+                }
+            }
+
+            for line in self.lines.iter() {
+                let (from, to) = self.line_ends(line);
+                let (from, to) = (from.scaled(w, h), to.scaled(w, h));
+                let Some(label) = self.weight_label(line, from, to) else {
+                    continue;
+                };
+                let (corner, end) = (label.box_from, label.box_to);
+                let _ = writeln!(
+                    svg,
+                    r#"  <rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" {}/>"#,
+                    corner.x,
+                    corner.y,
+                    end.x - corner.x,
+                    end.y - corner.y,
+                    paint("fill", self.background)
+                );
+                let _ = writeln!(
+                    svg,
+                    r#"  <text x="{:.2}" y="{:.2}" font-size="{WEIGHT_FONT_SIZE}" text-anchor="middle" dominant-baseline="central" {}>{}</text>"#,
+                    label.at.x,
+                    label.at.y,
+                    paint("fill", self.dot_style.color),
+                    label.text
+                );
+            }
+
+            let dots = self.dots.iter().zip(self.dot_colors.iter());
+            for (idx, (dot, color)) in dots.enumerate() {
+                let center = dot.scaled(w, h);
+                let radius = self.dot_radius(idx);
+                let _ = writeln!(
+                    svg,
+                    r#"  <circle cx="{:.2}" cy="{:.2}" r="{radius}" {}/>"#,
+                    center.x,
+                    center.y,
+                    paint("fill", *color)
+                );
+                if self.ids_inside {
+                    let size = inside_font_size(radius, idx.to_string().len());
+                    let _ = writeln!(
+                        svg,
+                        r#"  <text x="{:.2}" y="{:.2}" font-size="{size:.2}" text-anchor="middle" dominant-baseline="central" {}>{idx}</text>"#,
+                        center.x,
+                        center.y,
+                        paint("fill", self.background)
+                    );
+                }
+            }
+
+            svg.push_str("</svg>\n");
+            svg
+        }
+    }
+
+    /// `fill` or `stroke` attributes for the color, opacity included.
+    fn paint(attribute: &str, color: Color) -> String {
+        format!(
+            r#"{attribute}="rgb({},{},{})" {attribute}-opacity="{:.3}""#,
+            color.r,
+            color.g,
+            color.b,
+            color.a as f32 / 255.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_are_numbered_from_zero() {
+        let dir = std::env::temp_dir().join(format!("frames_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("temp dir is writable");
+        let config = RenderConfig::default().size(32, 32);
+        let frames: Vec<_> = (0..3)
+            .map(|count| {
+                let mut api = DrawingApi::new(&config);
+                for _ in 0..=count {
+                    api.draw_dot();
+                }
+                api.into_holder()
+            })
+            .collect();
+
+        let exported = export_frames(&frames, &dir, "step", &config);
+        let sizes: Vec<_> = (0..3)
+            .map(|idx| std::fs::metadata(dir.join(format!("step_00{idx}.png"))).map(|m| m.len()))
+            .collect();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        exported.expect("frames are written");
+        for size in sizes {
+            assert!(size.expect("frame exists") > 0);
+        }
+    }
+
+    #[test]
+    fn render_config_reaches_the_holder() {
+        let mut api = DrawingApi::new(&RenderConfig::default());
+        let dot = api.draw_dot();
+        api.draw_edge(dot, dot);
+        let holder = api.into_holder();
+        assert_eq!(holder.background, Color::rgb(0x20, 0x20, 0x20));
+        assert_eq!(holder.dot_style, DotStyle::default());
+        assert_eq!(holder.lines[0].style, EdgeStyle::default());
+
+        let dot_style = DotStyle {
+            color: Color::rgb(0xff, 0, 0),
+            radius: 8.0,
+        };
+        let edge_style = EdgeStyle {
+            color: Color::rgb(0, 0, 0xff),
+            width: 3.0,
+        };
+        let config = RenderConfig::default()
+            .background(Color::WHITE)
+            .dot_style(dot_style)
+            .edge_style(edge_style);
+        let mut api = DrawingApi::new(&config);
+        let dot = api.draw_dot();
+        api.draw_edge(dot, dot);
+        let holder = api.into_holder();
+        assert_eq!(holder.background, Color::WHITE);
+        assert_eq!(holder.dot_style, dot_style);
+        assert_eq!(holder.lines[0].style, edge_style);
+    }
+
+    #[test]
+    fn inside_font_grows_with_radius() {
+        for digits in 1..=3 {
+            let small = inside_font_size(8.0, digits);
+            let large = inside_font_size(16.0, digits);
+            assert!(
+                (large - 2.0 * small).abs() < 1e-4,
+                "{digits}: {small} {large}"
+            );
+        }
+        assert!(inside_font_size(10.0, 3) < inside_font_size(10.0, 1));
+        assert!(inside_font_size(10.0, 1) <= 2.0 * 10.0);
+
+        let config = RenderConfig::default().ids_inside(true);
+        assert_eq!(config.effective_dot_style().radius, INSIDE_ID_MIN_RADIUS);
+    }
+
+    #[test]
+    fn detail_drops_for_crowded_screens_only() {
+        assert_eq!(detail_level(100, 1.0), DetailLevel::Full);
+        assert_eq!(detail_level(500, 1.0), DetailLevel::Full);
+        assert_eq!(detail_level(501, 1.0), DetailLevel::Coarse);
+        assert_eq!(detail_level(10_000, 1.0), DetailLevel::Coarse);
+        assert_eq!(detail_level(10_000, 5.0), DetailLevel::Full);
+        assert_eq!(detail_level(100, 0.1), DetailLevel::Coarse);
+        assert_eq!(detail_level(1, 0.0), DetailLevel::Coarse);
+    }
+
+    #[test]
+    fn print_size_is_converted_to_pixels() {
+        assert_eq!(
+            pixel_size(6.0, 4.0, LengthUnit::Inch, 300.0),
+            Some((1800, 1200))
+        );
+        assert_eq!(
+            pixel_size(2.54, 5.08, LengthUnit::Cm, 100.0),
+            Some((100, 200))
+        );
+        assert_eq!(pixel_size(6.0, 0.0, LengthUnit::Inch, 300.0), None);
+        assert_eq!(pixel_size(6.0, 4.0, LengthUnit::Inch, f32::NAN), None);
+
+        let config = RenderConfig::default().print_size(6.0, 4.0, LengthUnit::Inch, 300.0);
+        let config = config.expect("size is valid");
+        assert_eq!((config.width, config.height), (1800, 1200));
+    }
+
+    #[test]
+    fn reciprocal_edges_bow_apart() {
+        let mut graph = crate::graph::with_dots_count(crate::graph::GraphBackend::EdgeList, 2);
+        graph.add_edges(&[(0, 1), (1, 0)]);
+        let holder = graph.to_holder(&RenderConfig::default()).unwrap();
+        let size = Coord::new(800.0, 600.0);
+
+        let controls: Vec<_> = holder
+            .lines
+            .iter()
+            .map(|line| {
+                let (from, to) = holder.line_ends(line);
+                let (from, to) = (
+                    Coord::new(from.x * size.x, from.y * size.y),
+                    Coord::new(to.x * size.x, to.y * size.y),
+                );
+                line.control(from, to).expect("reciprocal edges are bent")
+            })
+            .collect();
+        assert_eq!(controls.len(), 2);
+        assert!(
+            controls[0].distance(controls[1]) >= PARALLEL_EDGE_SPACING,
+            "{controls:?}"
+        );
+    }
+
+    #[test]
+    fn failed_window_falls_back_to_png() {
+        let path = std::env::temp_dir().join(format!("fallback_{}.png", std::process::id()));
+        let config = RenderConfig::default()
+            .size(64, 48)
+            .fallback(Some(path.clone()));
+        let mut api = DrawingApi::new(&config);
+        let dot = api.draw_dot();
+        api.draw_edge(dot, dot);
+
+        let outcome = fall_back(&api.into_holder(), &config, init_failure());
+        let written = std::fs::metadata(&path).map(|meta| meta.len());
+        let _ = std::fs::remove_file(&path);
+
+        match outcome {
+            Ok(DrawOutcome::FellBack {
+                path: fallback,
+                cause,
+            }) => {
+                assert_eq!(fallback, path);
+                assert!(matches!(cause, DrawError::BackendInit { .. }));
+            }
+            other => panic!("expected a fallback, got {other:?}"),
+        }
+        assert!(written.expect("fallback is written") > 0);
+    }
+
+    #[test]
+    fn same_seed_places_dots_the_same() {
+        let place = |seed| {
+            let mut api = DrawingApi::with_seed(&RenderConfig::default(), seed);
+            for _ in 0..8 {
+                api.draw_dot();
+            }
+            api.into_holder().dots
+        };
+
+        assert_eq!(place(42), place(42));
+        assert_ne!(place(42), place(43));
+    }
+
+    #[test]
+    fn svg_of_graph_has_a_circle_per_dot_and_a_line_per_edge() {
+        let mut api = DrawingApi::new(&RenderConfig::default());
+        let dots: Vec<_> = (0..10).map(|_| api.draw_dot()).collect();
+        for (from, to) in [(0, 1), (1, 2), (2, 0), (0, 4)] {
+            api.draw_edge(dots[from], dots[to]);
+        }
+        let svg = api.into_holder().to_svg(400, 300);
+
+        assert_eq!(svg.matches("<circle ").count(), 10);
+        assert_eq!(svg.matches("<line ").count(), 4);
+        assert!(svg.contains(r#"width="400" height="300""#), "{svg}");
+    }
+
+    #[test]
+    fn distance_to_segment_clamps_to_ends() {
+        let (from, to) = (Coord::new(0.0, 0.0), Coord::new(10.0, 0.0));
+        assert_eq!(distance_to_segment(Coord::new(5.0, 3.0), from, to), 3.0);
+        assert_eq!(distance_to_segment(Coord::new(14.0, 3.0), from, to), 5.0);
+        assert_eq!(distance_to_segment(Coord::new(3.0, 4.0), from, from), 5.0);
+    }
+
+    #[test]
+    fn line_at_picks_closest_line_within_threshold() {
+        let mut api = DrawingApi::new(&RenderConfig::default());
+        let dots = [(0.1, 0.1), (0.9, 0.1), (0.1, 0.5), (0.9, 0.5)]
+            .map(|(x, y)| api.draw_dot_at(Coord::new(x, y)));
+        api.draw_edge(dots[0], dots[1]);
+        api.draw_edge(dots[2], dots[3]);
+        let holder = api.into_holder();
+
+        let line_at = |x, y| holder.line_at(Coord::new(x, y), 100.0, 100.0, 4.0);
+        assert_eq!(line_at(50.0, 12.0), Some(0));
+        assert_eq!(line_at(50.0, 47.0), Some(1));
+        assert_eq!(line_at(50.0, 30.0), None);
+        assert_eq!(line_at(95.0, 10.0), None);
+    }
+
+    #[test]
+    fn png_dots_cover_their_lines() {
+        let red = EdgeStyle {
+            color: Color::rgb(0xff, 0, 0),
+            width: 4.0,
+        };
+        let config = RenderConfig::default().edge_style(red).size(100, 50);
+        let mut api = DrawingApi::new(&config);
+        let (from, to) = (
+            api.draw_dot_at(Coord::new(0.2, 0.5)),
+            api.draw_dot_at(Coord::new(0.8, 0.5)),
+        );
+        api.draw_edge(from, to);
+        let path = std::env::temp_dir().join(format!("covered_{}.png", std::process::id()));
+        render_to_png(&api.into_holder(), &path, &config).expect("png is written");
+        let saved = tiny_skia::Pixmap::load_png(&path);
+        let _ = std::fs::remove_file(&path);
+
+        let saved = saved.expect("png is readable");
+        let on_dot = saved.pixel(20, 25).expect("pixel is inside");
+        let on_line = saved.pixel(50, 25).expect("pixel is inside");
+        assert_eq!(
+            (on_dot.red(), on_dot.green(), on_dot.blue()),
+            (0xff, 0xff, 0xff)
+        );
+        assert_eq!(
+            (on_line.red(), on_line.green(), on_line.blue()),
+            (0xff, 0, 0)
+        );
+    }
+
+    #[test]
+    fn coincident_dots_stay_apart() {
+        let mut api = DrawingApi::with_seed(&RenderConfig::default(), 0);
+        let at = Coord::new(0.5, 0.5);
+        let (lhs, rhs) = (api.draw_dot_at(at), api.draw_dot_at(at));
+        let other = api.draw_dot_at(Coord::new(0.1, 0.1));
+        api.draw_edge(lhs, rhs);
+        api.draw_edge(rhs, other);
+        let mut holder = api.into_holder();
+
+        assert!(!holder.lines[0].is_loop());
+        assert_eq!(holder.dot_degrees(), vec![1, 2, 1]);
+        assert_eq!(holder.dot_degree(lhs), 1);
+
+        holder.move_dot(rhs, Coord::new(0.9, 0.9));
+        assert_eq!(
+            holder.line_ends(&holder.lines[0]),
+            (at, Coord::new(0.9, 0.9))
+        );
+        assert_eq!(holder.line_ends(&holder.lines[1]).0, Coord::new(0.9, 0.9));
+    }
+
+    fn init_failure() -> DrawError {
+        DrawError::BackendInit {
+            backend: DrawBackend::Egui,
+            message: "failed to start".to_owned(),
+        }
+    }
+
+    #[test]
+    fn fall_back_without_fallback_path_fails() {
+        let config = RenderConfig::default().fallback(None);
+        let result = fall_back(&GraphicsHolder::new(&config), &config, init_failure());
+        assert!(matches!(result, Err(DrawError::BackendInit { .. })));
+    }
+
+    #[test]
+    fn svg_has_one_element_per_shape() {
+        let config = RenderConfig::default().show_weights(true);
+        let mut api = DrawingApi::new(&config);
+        let dots =
+            [(0.1, 0.5), (0.5, 0.5), (0.9, 0.5)].map(|(x, y)| api.draw_dot_at(Coord::new(x, y)));
+        api.draw_edge(dots[0], dots[1]);
+        api.draw_edge(dots[1], dots[2]);
+        api.bend_last_edge(10.0);
+        api.draw_weighted_edge(dots[2], dots[2], 2.0);
+        let svg = api.into_holder().to_svg(200, 100);
+
+        let count = |tag: &str| svg.matches(&format!("<{tag} ")).count();
+        assert_eq!(count("line"), 1);
+        assert_eq!(count("path"), 1);
+        assert_eq!(count("circle"), 3 + 1);
+        assert_eq!(count("polyline"), 2);
+        assert_eq!(count("text"), 1);
+        assert_eq!(count("rect"), 1 + 1);
+    }
+
+    #[test]
+    fn toggles_hide_lines_and_lone_dots() {
+        let mut api = DrawingApi::new(&RenderConfig::default());
+        let dots = [0.1, 0.5, 0.9].map(|x| api.draw_dot_at(Coord::new(x, 0.5)));
+        api.draw_edge(dots[0], dots[1]);
+        let holder = api.into_holder();
+
+        let shown = holder.with_toggles(true, false);
+        assert_eq!(shown.lines, holder.lines);
+        assert_eq!(shown.dot_colors[dots[1]], holder.dot_colors[dots[1]]);
+        assert_eq!(shown.dot_colors[dots[2]].a, 0);
+
+        let shown = holder.with_toggles(false, false);
+        assert!(shown.lines.is_empty());
+        assert_eq!(shown.dot_colors[dots[0]], holder.dot_colors[dots[0]]);
+        assert_eq!(shown.dot_colors[dots[2]].a, 0);
+    }
+
+    #[test]
+    fn edges_need_both_dots() {
+        let mut api = DrawingApi::new(&RenderConfig::default());
+        let dot = api.draw_dot();
+        api.draw_edge(dot, dot + 1);
+        api.draw_edge(dot, dot);
+        let holder = api.into_holder();
+
+        assert_eq!(holder.lines.len(), 1);
+        assert!(holder.lines[0].is_loop());
+    }
+}
@@ -0,0 +1,98 @@
+use super::Coord;
+
+/// Edge widths weights are mapped onto, lightest to heaviest.
+pub const MIN_WEIGHT_WIDTH: f32 = 1.0;
+pub const MAX_WEIGHT_WIDTH: f32 = 6.0;
+
+const MARGIN: f32 = 10.0;
+const ROW_HEIGHT: f32 = 18.0;
+const SAMPLE_LENGTH: f32 = 30.0;
+const LABEL_GAP: f32 = 8.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LegendEntry {
+    pub weight: f32,
+    pub width: f32,
+}
+
+/// A single legend row in canvas pixels: a sample line and the label to its right.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegendRow {
+    pub from: Coord,
+    pub to: Coord,
+    pub width: f32,
+    /// Left-center anchor of the label.
+    pub label_at: Coord,
+    pub label: String,
+}
+
+/// Stroke width for `weight` when weights span `min..=max`.
+pub fn weight_width(weight: f32, min: f32, max: f32) -> f32 {
+    if max <= min {
+        return MIN_WEIGHT_WIDTH;
+    }
+    let share = ((weight - min) / (max - min)).clamp(0.0, 1.0);
+    MIN_WEIGHT_WIDTH + share * (MAX_WEIGHT_WIDTH - MIN_WEIGHT_WIDTH)
+}
+
+/// Legend for the minimum, median and maximum of `weights`, lightest first.
+///
+/// Empty when all the weights are the same, there is nothing to tell apart then.
+pub fn weight_legend(weights: &[f32]) -> Vec<LegendEntry> {
+    let mut sorted: Vec<_> = weights.iter().copied().filter(|w| w.is_finite()).collect();
+    sorted.sort_by(f32::total_cmp);
+
+    let (Some(&min), Some(&max)) = (sorted.first(), sorted.last()) else {
+        return vec![];
+    };
+    if min == max {
+        return vec![];
+    }
+
+    let median = sorted[sorted.len() / 2];
+    let mut entries: Vec<_> = [min, median, max]
+        .into_iter()
+        .map(|weight| LegendEntry {
+            weight,
+            width: weight_width(weight, min, max),
+        })
+        .collect();
+    entries.dedup_by(|lhs, rhs| lhs.weight == rhs.weight);
+    entries
+}
+
+/// Places legend rows in the bottom left corner of a `height` pixels high canvas,
+/// heaviest entry at the bottom.
+pub fn legend_rows(entries: &[LegendEntry], height: f32) -> Vec<LegendRow> {
+    entries
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(idx, entry)| {
+            let y = height - MARGIN - ROW_HEIGHT * (idx as f32 + 0.5);
+            LegendRow {
+                from: Coord::new(MARGIN, y),
+                to: Coord::new(MARGIN + SAMPLE_LENGTH, y),
+                width: entry.width,
+                label_at: Coord::new(MARGIN + SAMPLE_LENGTH + LABEL_GAP, y),
+                label: entry.weight.to_string(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legend_shows_min_median_and_max() {
+        let legend = weight_legend(&[4.0, 10.0, 2.0, f32::NAN, 7.0, 2.0]);
+        let weights: Vec<_> = legend.iter().map(|entry| entry.weight).collect();
+        let widths: Vec<_> = legend.iter().map(|entry| entry.width).collect();
+
+        assert_eq!(weights, vec![2.0, 4.0, 10.0]);
+        assert_eq!(widths, vec![MIN_WEIGHT_WIDTH, 2.25, MAX_WEIGHT_WIDTH]);
+        assert!(weight_legend(&[3.0, 3.0]).is_empty());
+    }
+}
@@ -0,0 +1,660 @@
+use std::collections::{HashMap, VecDeque};
+
+use rand::Rng;
+
+use crate::{
+    graph::{self, Graph, GraphBackend, WeightedGraph},
+    gui::{Color, Coord, EdgeStyle},
+};
+
+/// Strategy for placing dots inside the unit square.
+#[derive(Debug, Clone)]
+pub enum Layout {
+    Random,
+    /// Fruchterman–Reingold spring embedding: nodes push each other apart, edges pull
+    /// their ends together in proportion to their weight, moves shrink every iteration.
+    /// Starts from random positions.
+    ForceDirected {
+        iterations: usize,
+    },
+    /// BFS spanning tree from `root` drawn top-down, remaining edges drawn as back edges.
+    /// Nodes unreachable from `root` start their own trees, to the right of it.
+    SpanningTree {
+        root: usize,
+        tree_edge: EdgeStyle,
+        back_edge: EdgeStyle,
+    },
+    /// Lays out every connected component with the inner layout on its own,
+    /// then packs the components into disjoint boxes sized by their node count.
+    Components(Box<Layout>),
+    /// Row-major grid of `ceil(sqrt(n))` columns and rows, the last row may be partially filled.
+    Grid,
+    /// Puts nodes of the same group on a small circle, groups are spread over the canvas.
+    /// Holds the group of every node, nodes past its end are groups of their own.
+    Clustered(Vec<usize>),
+    /// Position of every node given by the caller, e.g. a saved layout.
+    /// Drawing checks there's exactly one per node.
+    Fixed(Vec<Coord>),
+}
+
+/// Result of a layout run.
+#[derive(Debug, Clone, Default)]
+pub struct Placement {
+    pub coords: Vec<Coord>,
+    /// Style overrides for edges in `for_each_edge` order,
+    /// edges past its end are drawn with the configured edge style.
+    pub edge_styles: Vec<EdgeStyle>,
+}
+
+impl Layout {
+    pub fn force_directed() -> Self {
+        Layout::ForceDirected {
+            iterations: FORCE_ITERATIONS,
+        }
+    }
+
+    pub fn spanning_tree(root: usize) -> Self {
+        Layout::SpanningTree {
+            root,
+            tree_edge: EdgeStyle {
+                color: Color::WHITE,
+                width: 1.5,
+            },
+            back_edge: EdgeStyle {
+                color: Color::rgba(0xff, 0xff, 0xff, 0x50),
+                width: 1.0,
+            },
+        }
+    }
+
+    pub fn place(&self, graph: &dyn WeightedGraph) -> Placement {
+        match self {
+            Layout::Random => {
+                let coords = crate::random::with_rng(|gen| {
+                    (0..graph.dot_count())
+                        .map(|_| Coord::new(gen.gen_range(0.0..1.0), gen.gen_range(0.0..1.0)))
+                        .collect()
+                });
+
+                Placement {
+                    coords,
+                    edge_styles: vec![],
+                }
+            }
+            Layout::ForceDirected { iterations } => Placement {
+                coords: place_force_directed(graph, *iterations),
+                edge_styles: vec![],
+            },
+            Layout::SpanningTree {
+                root,
+                tree_edge,
+                back_edge,
+            } => {
+                let forest = BfsForest::new(graph, *root);
+                let mut claimed = vec![false; forest.parent.len()];
+
+                let mut edge_styles = vec![];
+                graph.for_each_edge(&mut |from, to| {
+                    let style = if forest.claim_tree_edge(from, to, &mut claimed) {
+                        *tree_edge
+                    } else {
+                        *back_edge
+                    };
+                    edge_styles.push(style)
+                });
+
+                Placement {
+                    coords: forest.coords(),
+                    edge_styles,
+                }
+            }
+            Layout::Components(inner) => place_components(graph, inner),
+            Layout::Clustered(groups) => Placement {
+                coords: place_clusters(graph.dot_count(), groups),
+                edge_styles: vec![],
+            },
+            Layout::Grid => Placement {
+                coords: place_grid(graph.dot_count()),
+                edge_styles: vec![],
+            },
+            Layout::Fixed(coords) => Placement {
+                coords: coords.iter().copied().take(graph.dot_count()).collect(),
+                edge_styles: vec![],
+            },
+        }
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout::force_directed()
+    }
+}
+
+/// Iterations of `Layout::force_directed()`, enough for a few hundred nodes to settle
+/// while keeping the quadratic work per iteration well under a second.
+pub const FORCE_ITERATIONS: usize = 100;
+
+/// Strength of the pull towards the center in a force-directed layout.
+const FORCE_GRAVITY: f32 = 4.0;
+
+/// Free space left around a force-directed layout so dots on its border aren't cut off.
+const FORCE_MARGIN: f32 = 0.05;
+
+fn place_force_directed(graph: &dyn WeightedGraph, iterations: usize) -> Vec<Coord> {
+    let count = graph.dot_count();
+    let mut coords: Vec<_> = crate::random::with_rng(|gen| {
+        (0..count)
+            .map(|_| Coord::new(gen.gen_range(0.0..1.0), gen.gen_range(0.0..1.0)))
+            .collect()
+    });
+    if count < 2 {
+        return coords;
+    }
+
+    // Heavier edges pull harder, so their ends settle closer together.
+    let mut edges = vec![];
+    graph.for_each_weighted_edge(&mut |from, to, weight| {
+        if from < count && to < count && from != to {
+            let weight = if weight.is_finite() && weight > 0.0 {
+                weight
+            } else {
+                1.0
+            };
+            edges.push((from, to, weight));
+        }
+    });
+
+    // Ideal edge length for nodes spread evenly over the unit square.
+    let k = (1.0 / count as f32).sqrt();
+    let mut shift = vec![(0.0f32, 0.0f32); count];
+    for iteration in 0..iterations {
+        shift.fill((0.0, 0.0));
+        for first in 0..count {
+            for second in first + 1..count {
+                let (dx, dy) = (
+                    coords[first].x - coords[second].x,
+                    coords[first].y - coords[second].y,
+                );
+                let dist = dx.hypot(dy).max(0.01 * k);
+                let push = k * k / dist / dist;
+                shift[first].0 += dx * push;
+                shift[first].1 += dy * push;
+                shift[second].0 -= dx * push;
+                shift[second].1 -= dy * push;
+            }
+        }
+        for &(from, to, weight) in edges.iter() {
+            let (dx, dy) = (coords[from].x - coords[to].x, coords[from].y - coords[to].y);
+            let pull = weight * dx.hypot(dy) / k;
+            shift[from].0 -= dx * pull;
+            shift[from].1 -= dy * pull;
+            shift[to].0 += dx * pull;
+            shift[to].1 += dy * pull;
+        }
+
+        // Pulls everything towards the center so disconnected parts don't drift apart.
+        for (coord, shift) in coords.iter().zip(shift.iter_mut()) {
+            shift.0 -= (coord.x - 0.5) * FORCE_GRAVITY;
+            shift.1 -= (coord.y - 0.5) * FORCE_GRAVITY;
+        }
+
+        let temperature = 0.1 * (1.0 - iteration as f32 / iterations as f32);
+        for (coord, &(dx, dy)) in coords.iter_mut().zip(shift.iter()) {
+            let len = dx.hypot(dy);
+            if len > f32::EPSILON {
+                let step = len.min(temperature) / len;
+                coord.x += dx * step;
+                coord.y += dy * step;
+            }
+        }
+    }
+
+    fit_to_view(&coords, FORCE_MARGIN)
+}
+
+/// Distance between packed component boxes, relative to the box side.
+const COMPONENT_GAP: f32 = 0.15;
+
+fn place_components(graph: &dyn WeightedGraph, inner: &Layout) -> Placement {
+    let component_of = graph::connected_components(graph);
+    let component_count = component_of.iter().copied().max().map_or(0, |max| max + 1);
+
+    let mut members = vec![vec![]; component_count];
+    let mut local_idx = vec![0; component_of.len()];
+    for (node, &component) in component_of.iter().enumerate() {
+        local_idx[node] = members[component].len();
+        members[component].push(node);
+    }
+
+    let mut subgraphs: Vec<_> = members
+        .iter()
+        .map(|nodes| graph::with_dots_count(GraphBackend::EdgeList, nodes.len()))
+        .collect();
+    graph.for_each_weighted_edge(&mut |from, to, weight| {
+        if let (Some(&component), Some(_)) = (component_of.get(from), component_of.get(to)) {
+            subgraphs[component].add_weighted_edge(local_idx[from], local_idx[to], weight);
+        }
+    });
+
+    let placements: Vec<_> = subgraphs
+        .iter()
+        .map(|subgraph| inner.place(subgraph.as_weighted()))
+        .collect();
+    let sides: Vec<_> = members
+        .iter()
+        .map(|nodes| (nodes.len() as f32).sqrt())
+        .collect();
+    let boxes = shelf_pack(&sides);
+
+    let mut coords = vec![Coord::new(0.5, 0.5); component_of.len()];
+    for (component, placement) in placements.iter().enumerate() {
+        let (corner, side) = boxes[component];
+        let pad = side * COMPONENT_GAP / 2.0;
+        let fitted = fit_to_unit(&placement.coords);
+        for (local, coord) in fitted.into_iter().enumerate() {
+            coords[members[component][local]] = Coord::new(
+                corner.x + pad + coord.x * (side - 2.0 * pad),
+                corner.y + pad + coord.y * (side - 2.0 * pad),
+            );
+        }
+    }
+
+    let mut edge_styles = vec![];
+    let mut next_style = vec![0; component_count];
+    let mut styled = true;
+    graph.for_each_edge(&mut |from, _| {
+        let style = component_of.get(from).and_then(|&component| {
+            let idx = next_style[component];
+            next_style[component] += 1;
+            placements[component].edge_styles.get(idx)
+        });
+        match (styled, style) {
+            (true, Some(style)) => edge_styles.push(*style),
+            _ => styled = false,
+        }
+    });
+
+    Placement {
+        coords,
+        edge_styles,
+    }
+}
+
+/// Every node in the middle of its cell of a square grid covering the unit square.
+fn place_grid(count: usize) -> Vec<Coord> {
+    let side = (count as f64).sqrt().ceil().max(1.0) as usize;
+    (0..count)
+        .map(|node| {
+            let (row, col) = (node / side, node % side);
+            Coord::new(
+                (col as f32 + 0.5) / side as f32,
+                (row as f32 + 0.5) / side as f32,
+            )
+        })
+        .collect()
+}
+
+fn place_clusters(count: usize, groups: &[usize]) -> Vec<Coord> {
+    let mut members: Vec<Vec<usize>> = vec![];
+    let mut group_idx = HashMap::new();
+    for node in 0..count {
+        let key = groups.get(node).map_or(Err(node), |&group| Ok(group));
+        let idx = *group_idx.entry(key).or_insert_with(|| {
+            members.push(vec![]);
+            members.len() - 1
+        });
+        members[idx].push(node);
+    }
+
+    let sides: Vec<_> = members
+        .iter()
+        .map(|nodes| (nodes.len() as f32).sqrt())
+        .collect();
+    let boxes = shelf_pack(&sides);
+
+    let mut coords = vec![Coord::new(0.5, 0.5); count];
+    for (nodes, (corner, side)) in members.iter().zip(boxes) {
+        let center = Coord::new(corner.x + side / 2.0, corner.y + side / 2.0);
+        let radius = if nodes.len() > 1 {
+            side * (1.0 - COMPONENT_GAP) / 2.0
+        } else {
+            0.0
+        };
+        for (idx, &node) in nodes.iter().enumerate() {
+            let angle = std::f32::consts::TAU * idx as f32 / nodes.len() as f32;
+            coords[node] = Coord::new(
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            );
+        }
+    }
+    coords
+}
+
+/// Packs squares with the given sides into rows, biggest first, and scales the result
+/// into the unit square. Gives the top left corner and the side of every square.
+fn shelf_pack(sides: &[f32]) -> Vec<(Coord, f32)> {
+    let mut order: Vec<_> = (0..sides.len()).collect();
+    order.sort_by(|&lhs, &rhs| sides[rhs].total_cmp(&sides[lhs]));
+
+    let area: f32 = sides.iter().map(|side| side * side).sum();
+    let biggest = order.first().map_or(1.0, |&idx| sides[idx]);
+    let shelf_width = area.sqrt().max(biggest);
+
+    let mut boxes = vec![(Coord::new(0.0, 0.0), 0.0); sides.len()];
+    let (mut x, mut y, mut shelf_height) = (0.0f32, 0.0f32, 0.0f32);
+    let mut width = 0.0f32;
+    for idx in order {
+        let side = sides[idx];
+        if x > 0.0 && x + side > shelf_width {
+            y += shelf_height;
+            x = 0.0;
+            shelf_height = 0.0;
+        }
+        boxes[idx] = (Coord::new(x, y), side);
+        x += side;
+        width = width.max(x);
+        shelf_height = shelf_height.max(side);
+    }
+
+    let height = y + shelf_height;
+    let scale = 1.0 / width.max(height).max(f32::EPSILON);
+    let offset = Coord::new((1.0 - width * scale) / 2.0, (1.0 - height * scale) / 2.0);
+    boxes
+        .into_iter()
+        .map(|(corner, side)| {
+            let corner = Coord::new(offset.x + corner.x * scale, offset.y + corner.y * scale);
+            (corner, side * scale)
+        })
+        .collect()
+}
+
+/// Stretches `coords` to span the unit square less `margin` on every side,
+/// a lone point or line ends up centered across. `margin` is clamped to `0.0..=0.5`.
+pub fn fit_to_view(coords: &[Coord], margin: f32) -> Vec<Coord> {
+    let margin = if margin.is_nan() {
+        0.0
+    } else {
+        margin.clamp(0.0, 0.5)
+    };
+    let inner = 1.0 - 2.0 * margin;
+    fit_to_unit(coords)
+        .into_iter()
+        .map(|c| Coord::new(margin + c.x * inner, margin + c.y * inner))
+        .collect()
+}
+
+/// Stretches `coords` to span the unit square, a lone point or line ends up centered across.
+fn fit_to_unit(coords: &[Coord]) -> Vec<Coord> {
+    let fold = |init: f32, pick: fn(f32, f32) -> f32, axis: fn(&Coord) -> f32| {
+        coords.iter().map(axis).fold(init, pick)
+    };
+    let (min_x, max_x) = (
+        fold(f32::MAX, f32::min, |c| c.x),
+        fold(f32::MIN, f32::max, |c| c.x),
+    );
+    let (min_y, max_y) = (
+        fold(f32::MAX, f32::min, |c| c.y),
+        fold(f32::MIN, f32::max, |c| c.y),
+    );
+
+    let stretch = |value: f32, min: f32, max: f32| {
+        if max - min > f32::EPSILON {
+            (value - min) / (max - min)
+        } else {
+            0.5
+        }
+    };
+    coords
+        .iter()
+        .map(|c| Coord::new(stretch(c.x, min_x, max_x), stretch(c.y, min_y, max_y)))
+        .collect()
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EdgePartition {
+    pub tree: Vec<(usize, usize)>,
+    pub back: Vec<(usize, usize)>,
+}
+
+/// Splits edges into those of the BFS spanning forest rooted at `root` and the remaining back edges.
+///
+/// Edges are treated as undirected. Of several parallel edges only the first one is a tree edge.
+pub fn partition_edges(graph: &dyn Graph, root: usize) -> EdgePartition {
+    let forest = BfsForest::new(graph, root);
+    let mut claimed = vec![false; forest.parent.len()];
+
+    let mut partition = EdgePartition::default();
+    graph.for_each_edge(&mut |from, to| {
+        if forest.claim_tree_edge(from, to, &mut claimed) {
+            partition.tree.push((from, to))
+        } else {
+            partition.back.push((from, to))
+        }
+    });
+    partition
+}
+
+struct BfsForest {
+    parent: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    depth: Vec<usize>,
+    roots: Vec<usize>,
+    /// All nodes, parents always before their children.
+    order: Vec<usize>,
+}
+
+impl BfsForest {
+    fn new(graph: &dyn Graph, root: usize) -> Self {
+        let count = graph.dot_count();
+        let mut adjacent = vec![vec![]; count];
+        graph.for_each_edge(&mut |from, to| {
+            if from < count && to < count && from != to {
+                adjacent[from].push(to);
+                adjacent[to].push(from);
+            }
+        });
+        for list in adjacent.iter_mut() {
+            list.sort_unstable();
+            list.dedup();
+        }
+
+        let mut forest = Self {
+            parent: vec![None; count],
+            children: vec![vec![]; count],
+            depth: vec![0; count],
+            roots: vec![],
+            order: Vec::with_capacity(count),
+        };
+        let mut visited = vec![false; count];
+        let mut queue = VecDeque::new();
+
+        for start in std::iter::once(root).chain(0..count) {
+            if start >= count || visited[start] {
+                continue;
+            }
+            visited[start] = true;
+            forest.roots.push(start);
+            queue.push_back(start);
+
+            while let Some(node) = queue.pop_front() {
+                forest.order.push(node);
+                for &next in adjacent[node].iter() {
+                    if !visited[next] {
+                        visited[next] = true;
+                        forest.parent[next] = Some(node);
+                        forest.depth[next] = forest.depth[node] + 1;
+                        forest.children[node].push(next);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        forest
+    }
+
+    fn claim_tree_edge(&self, from: usize, to: usize, claimed: &mut [bool]) -> bool {
+        let mut claim = |child: usize, parent: usize| {
+            let is_tree = self.parent.get(child) == Some(&Some(parent)) && !claimed[child];
+            if is_tree {
+                claimed[child] = true;
+            }
+            is_tree
+        };
+        claim(to, from) || claim(from, to)
+    }
+
+    /// Leaves get evenly spread columns in DFS order, parents are centered over their children.
+    fn coords(&self) -> Vec<Coord> {
+        let count = self.parent.len();
+        let mut column = vec![0.0f32; count];
+        let mut leaves = 0usize;
+
+        let mut stack: Vec<_> = self.roots.iter().rev().copied().collect();
+        while let Some(node) = stack.pop() {
+            if self.children[node].is_empty() {
+                column[node] = leaves as f32;
+                leaves += 1;
+            }
+            stack.extend(self.children[node].iter().rev().copied());
+        }
+
+        for &node in self.order.iter().rev() {
+            if let (Some(&first), Some(&last)) =
+                (self.children[node].first(), self.children[node].last())
+            {
+                column[node] = (column[first] + column[last]) / 2.0;
+            }
+        }
+
+        let levels = self.depth.iter().copied().max().map_or(1, |max| max + 1);
+        (0..count)
+            .map(|node| {
+                Coord::new(
+                    (column[node] + 0.5) / leaves.max(1) as f32,
+                    (self.depth[node] as f32 + 0.5) / levels as f32,
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The graph `main.rs` draws: a triangle with a tail and isolated nodes.
+    fn sample() -> Box<dyn graph::DrawableGraph> {
+        let mut graph = graph::with_dots_count(GraphBackend::EdgeList, 10);
+        for (from, to) in [(0, 1), (1, 2), (2, 0), (0, 4)] {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+
+    #[test]
+    fn triangle_has_one_back_edge() {
+        let graph = sample();
+        assert_eq!(
+            partition_edges(&*graph, 0),
+            EdgePartition {
+                tree: vec![(0, 1), (2, 0), (0, 4)],
+                back: vec![(1, 2)],
+            }
+        );
+    }
+
+    /// Smallest and largest corner of the box around `coords`.
+    fn bounds(coords: &[Coord]) -> (Coord, Coord) {
+        coords.iter().fold(
+            (
+                Coord::new(f32::MAX, f32::MAX),
+                Coord::new(f32::MIN, f32::MIN),
+            ),
+            |(min, max), at| {
+                (
+                    Coord::new(min.x.min(at.x), min.y.min(at.y)),
+                    Coord::new(max.x.max(at.x), max.y.max(at.y)),
+                )
+            },
+        )
+    }
+
+    #[test]
+    fn components_get_disjoint_boxes() {
+        let mut graph = graph::with_dots_count(GraphBackend::EdgeList, 5);
+        for (from, to) in [(0, 1), (1, 2), (2, 0), (3, 4)] {
+            graph.add_edge(from, to);
+        }
+
+        let coords = Layout::Components(Box::new(Layout::Random))
+            .place(&*graph)
+            .coords;
+        let (lhs_min, lhs_max) = bounds(&coords[..3]);
+        let (rhs_min, rhs_max) = bounds(&coords[3..]);
+
+        let overlap_x = lhs_min.x <= rhs_max.x && rhs_min.x <= lhs_max.x;
+        let overlap_y = lhs_min.y <= rhs_max.y && rhs_min.y <= lhs_max.y;
+        assert!(!(overlap_x && overlap_y), "{coords:?}");
+    }
+
+    #[test]
+    fn clustered_groups_stay_together() {
+        let groups = vec![0, 1, 2, 0, 1, 2, 0, 1, 2];
+        let graph = graph::with_dots_count(GraphBackend::EdgeList, groups.len());
+        let coords = Layout::Clustered(groups.clone()).place(&*graph).coords;
+
+        let (mut within, mut across) = (vec![], vec![]);
+        for lhs in 0..coords.len() {
+            for rhs in lhs + 1..coords.len() {
+                let distance = coords[lhs].distance(coords[rhs]);
+                if groups[lhs] == groups[rhs] {
+                    within.push(distance);
+                } else {
+                    across.push(distance);
+                }
+            }
+        }
+        let mean = |distances: &[f32]| distances.iter().sum::<f32>() / distances.len() as f32;
+        assert!(mean(&within) < mean(&across), "{coords:?}");
+    }
+
+    #[test]
+    fn heavy_edge_ends_closer_than_light_ones() {
+        // A ring keeps the layout round, so fitting it to the view stretches both axes alike.
+        let mut graph = graph::with_dots_count(GraphBackend::EdgeList, 9);
+        for node in 0..8 {
+            graph.add_weighted_edge(node, (node + 1) % 8, 1.0);
+        }
+        graph.add_weighted_edge(0, 8, 20.0);
+
+        crate::random::set_global_seed(3);
+        let coords = Layout::ForceDirected { iterations: 300 }
+            .place(graph.as_weighted())
+            .coords;
+        crate::random::clear_global_seed();
+
+        let heavy = coords[0].distance(coords[8]);
+        let shortest_light = (0..8)
+            .map(|node| coords[node].distance(coords[(node + 1) % 8]))
+            .fold(f32::MAX, f32::min);
+        assert!(heavy < shortest_light, "{coords:?}");
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_layout() {
+        let graph = sample();
+        let place = |seed| {
+            crate::random::set_global_seed(seed);
+            let coords = Layout::ForceDirected { iterations: 50 }
+                .place(graph.as_weighted())
+                .coords;
+            crate::random::clear_global_seed();
+            coords
+        };
+
+        assert_eq!(place(7), place(7));
+        assert_ne!(place(7), place(8));
+    }
+}
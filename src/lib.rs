@@ -1,2 +1,4 @@
 pub mod graph;
-pub mod gui;
\ No newline at end of file
+pub mod gui;
+pub mod layout;
+pub mod random;
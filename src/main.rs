@@ -1,6 +1,38 @@
-use graph_bridge::graph;
+use std::{io::Read, path::{Path, PathBuf}, time::Duration};
+
+use graph_bridge::{
+    graph,
+    gui::{Color, DotStyle, DrawOutcome, EdgeStyle, RenderConfig},
+    layout::Layout,
+};
 use clap::Parser;
 
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum StdinFormat {
+    /// `{"dots": 3, "edges": [[0, 1], [1, 2]]}`
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum LayoutKind {
+    /// Uniformly random positions
+    Random,
+    /// Spring embedding, edges pull their ends together
+    Force,
+    /// Row-major square grid by node index
+    Grid,
+}
+
+impl From<LayoutKind> for Layout {
+    fn from(kind: LayoutKind) -> Self {
+        match kind {
+            LayoutKind::Random => Layout::Random,
+            LayoutKind::Force => Layout::force_directed(),
+            LayoutKind::Grid => Layout::Grid,
+        }
+    }
+}
+
 #[derive(Debug, Parser)]
 #[command(author, version, about, long_about = None)]
 struct Args {
@@ -12,16 +44,239 @@ struct Args {
         long,
     )]
     draw_backend: graph_bridge::gui::DrawBackend,
+
+    /// Node count of the built-in sample; its edges still add any node past the count they need
+    #[arg(short = 'n', long, default_value_t = 10)]
+    dots: usize,
+
+    /// Edge of the built-in sample as FROM,TO, replaces its edges; repeat for more edges
+    #[arg(
+        long = "edge",
+        value_name = "FROM,TO",
+        value_parser = parse_edge,
+        conflicts_with_all = ["stdin_format", "input", "csv", "random"],
+    )]
+    edges: Vec<(usize, usize)>,
+
+    /// Read the graph from stdin instead of drawing the built-in sample
+    #[arg(long, conflicts_with_all = ["input", "csv", "random"])]
+    stdin_format: Option<StdinFormat>,
+
+    /// Read the graph from a Graphviz DOT file instead of drawing the built-in sample
+    #[arg(long, conflicts_with_all = ["csv", "random"])]
+    input: Option<PathBuf>,
+
+    /// Read the graph from a `from,to` CSV file instead of drawing the built-in sample
+    #[arg(long, conflicts_with = "random")]
+    csv: Option<PathBuf>,
+
+    /// Draw a random graph on N nodes where every pair is connected with probability P,
+    /// seeded by --layout-seed when it's given
+    #[arg(long, num_args = 2, value_names = ["N", "P"])]
+    random: Option<Vec<f64>>,
+
+    /// File written by the png draw backend
+    #[arg(long, default_value = graph_bridge::gui::DEFAULT_OUTPUT_PATH)]
+    output: PathBuf,
+
+    /// Image width in pixels for the png draw backend
+    #[arg(long, default_value_t = 800)]
+    width: u32,
+
+    /// Image height in pixels for the png draw backend
+    #[arg(long, default_value_t = 600)]
+    height: u32,
+
+    /// Also write the graph to a Graphviz DOT file, without edges removed in the window
+    #[arg(long)]
+    export_dot: Option<PathBuf>,
+
+    /// Background color as #RRGGBB or #RRGGBBAA
+    #[arg(long)]
+    bg: Option<Color>,
+
+    /// Dot color as #RRGGBB or #RRGGBBAA
+    #[arg(long)]
+    node_color: Option<Color>,
+
+    /// Edge color as #RRGGBB or #RRGGBBAA
+    #[arg(long)]
+    edge_color: Option<Color>,
+
+    /// Don't draw arrowheads on the edges of directed graphs
+    #[arg(long)]
+    no_arrows: bool,
+
+    /// How dots are placed
+    #[arg(long, value_enum, default_value_t = LayoutKind::Force)]
+    layout: LayoutKind,
+
+    /// Give every connected component its own dot color
+    #[arg(long)]
+    color_components: bool,
+
+    /// Draw dots bigger the more edges they have
+    #[arg(long)]
+    size_by_degree: bool,
+
+    /// Write edge weights over the edges
+    #[arg(long)]
+    show_weights: bool,
+
+    /// Highlight a path with the smallest total weight between two nodes
+    #[arg(long, num_args = 2, value_names = ["FROM", "TO"])]
+    path: Option<Vec<usize>>,
+
+    /// Milliseconds between edges appearing one by one in a window
+    #[arg(long, value_name = "MS")]
+    animate: Option<u64>,
+
+    /// Seed for every random choice, so the same seed draws the same picture
+    #[arg(long)]
+    layout_seed: Option<u64>,
 }
 
 fn main() {
     let args = Args::parse();
+    if let Some(seed) = args.layout_seed {
+        graph_bridge::random::set_global_seed(seed);
+    }
+
+    let mut graph = if let Some(path) = &args.input {
+        read_file(path, |text| graph::from_dot(text, args.graph_backend))
+    } else if let Some(path) = &args.csv {
+        read_file(path, |text| graph::from_csv(text, args.graph_backend))
+    } else if let Some(&[n, p]) = args.random.as_deref() {
+        random_graph(n, p, args.graph_backend)
+    } else if let Some(format) = args.stdin_format {
+        read_stdin(format, args.graph_backend)
+    } else {
+        sample_graph(args.graph_backend, args.dots, &args.edges)
+    };
 
-    let mut graph = graph::with_dots_count(args.graph_backend, 10);
-    graph.add_edge(0, 1);
-    graph.add_edge(1, 2);
-    graph.add_edge(2, 0);
-    graph.add_edge(0, 4);
+    let mut config = RenderConfig::default()
+        .layout(args.layout.into())
+        .output(args.output)
+        .size(args.width, args.height)
+        .arrows(!args.no_arrows)
+        .color_components(args.color_components)
+        .size_by_degree(args.size_by_degree)
+        .show_weights(args.show_weights)
+        .animate(args.animate.map(Duration::from_millis));
+    if let Some(background) = args.bg {
+        config = config.background(background);
+    }
+    if let Some(color) = args.node_color {
+        config = config.dot_style(DotStyle {
+            color,
+            ..Default::default()
+        });
+    }
+    if let Some(color) = args.edge_color {
+        config = config.edge_style(EdgeStyle {
+            color,
+            ..Default::default()
+        });
+    }
+    if let Some(&[from, to]) = args.path.as_deref() {
+        match graph::shortest_weighted_path(graph.as_weighted(), from, to) {
+            Some((_, path)) => config = config.highlight_path(path),
+            None => eprintln!("No path from {from} to {to}, drawing the graph without one"),
+        }
+    }
+    let drawn = graph.edit(args.draw_backend, &config);
+    // Written after the window closes, so edges removed in it are left out.
+    if let Some(path) = &args.export_dot {
+        if let Err(err) = std::fs::write(path, graph::to_dot(graph.as_graph())) {
+            eprintln!("Failed to write {}: {err}", path.display());
+            std::process::exit(1);
+        }
+    }
+    match drawn {
+        Ok(DrawOutcome::FellBack { path, cause }) => {
+            eprintln!("{cause}, saved the graph to {} instead", path.display())
+        }
+        Ok(_) => {}
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Graph with `dots` nodes and `edges`, or a few edges between the first five without them.
+/// Fewer `dots` grow to fit the edges like any `add_edge` past `dot_count()` does.
+fn sample_graph(
+    backend: graph::GraphBackend,
+    dots: usize,
+    edges: &[(usize, usize)],
+) -> Box<dyn graph::DrawableGraph> {
+    let mut graph = graph::with_dots_count(backend, dots);
+    if edges.is_empty() {
+        graph.add_edges(&[(0, 1), (1, 2), (2, 0), (0, 4)]);
+    } else {
+        graph.add_edges(edges);
+    }
+    graph
+}
+
+fn parse_edge(text: &str) -> Result<(usize, usize), String> {
+    let (from, to) = text
+        .split_once(',')
+        .ok_or_else(|| format!("expected FROM,TO, got '{text}'"))?;
+    let node = |id: &str| {
+        id.trim()
+            .parse()
+            .map_err(|_| format!("'{}' is not a node index", id.trim()))
+    };
+    Ok((node(from)?, node(to)?))
+}
+
+fn random_graph(n: f64, p: f64, backend: graph::GraphBackend) -> Box<dyn graph::DrawableGraph> {
+    if n < 0.0 || n.fract() != 0.0 {
+        eprintln!("--random: node count must be a non-negative integer, got {n}");
+        std::process::exit(1);
+    }
+    if !(0.0..=1.0).contains(&p) {
+        eprintln!("--random: edge probability must be between 0 and 1, got {p}");
+        std::process::exit(1);
+    }
+    let seed = graph_bridge::random::with_rng(|gen| gen.next_u64());
+    graph::gnp(backend, n as usize, p, seed)
+}
+
+#[cfg_attr(not(feature = "serde"), allow(unused_variables))]
+fn read_stdin(format: StdinFormat, backend: graph::GraphBackend) -> Box<dyn graph::DrawableGraph> {
+    let mut input = String::new();
+    if let Err(err) = std::io::stdin().read_to_string(&mut input) {
+        eprintln!("Failed to read stdin: {err}");
+        std::process::exit(1);
+    }
+
+    #[cfg(feature = "serde")]
+    let parsed = match format {
+        StdinFormat::Json => graph::from_json(&input, backend),
+    };
+    #[cfg(not(feature = "serde"))]
+    let parsed: Result<_, &str> = match format {
+        StdinFormat::Json => Err("reading JSON needs the serde feature"),
+    };
+    parsed.unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(1);
+    })
+}
 
-    graph.draw(args.draw_backend)
+fn read_file<E: std::fmt::Display>(
+    path: &Path,
+    parse: impl FnOnce(&str) -> Result<Box<dyn graph::DrawableGraph>, E>,
+) -> Box<dyn graph::DrawableGraph> {
+    let text = std::fs::read_to_string(path).unwrap_or_else(|err| {
+        eprintln!("Failed to read {}: {err}", path.display());
+        std::process::exit(1);
+    });
+    parse(&text).unwrap_or_else(|err| {
+        eprintln!("{}: {err}", path.display());
+        std::process::exit(1);
+    })
 }
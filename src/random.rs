@@ -0,0 +1,33 @@
+//! Single source of randomness for layouts and generators.
+//!
+//! Everything is drawn from `thread_rng()` unless a seed is set with [`set_global_seed`],
+//! in which case the thread replays the same random choices on every run. The seed is kept
+//! per thread, so tests running side by side can't disturb each other's sequences.
+
+use std::cell::RefCell;
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+thread_local! {
+    static SEEDED: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+}
+
+/// Makes every following random operation on this thread draw from an RNG seeded with `seed`.
+pub fn set_global_seed(seed: u64) {
+    SEEDED.with(|seeded| *seeded.borrow_mut() = Some(StdRng::seed_from_u64(seed)));
+}
+
+/// Goes back to unseeded randomness on this thread.
+pub fn clear_global_seed() {
+    SEEDED.with(|seeded| *seeded.borrow_mut() = None);
+}
+
+/// Runs `f` with the seeded RNG if there is one, with `thread_rng()` otherwise.
+///
+/// `f` must not call back into `with_rng`.
+pub fn with_rng<R>(f: impl FnOnce(&mut dyn RngCore) -> R) -> R {
+    SEEDED.with(|seeded| match seeded.borrow_mut().as_mut() {
+        Some(rng) => f(rng),
+        None => f(&mut rand::thread_rng()),
+    })
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "serde")]
+
+use std::{
+    io::Write,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+};
+
+/// Runs the binary with `json` on stdin, drawing to an SVG named after `name`.
+fn draw_json(name: &str, json: &str) -> (Output, PathBuf) {
+    let output = std::env::temp_dir().join(format!("{name}_{}.png", std::process::id()));
+    let mut child = Command::new(env!("CARGO_BIN_EXE_graph-bridge"))
+        .args(["-g", "adjacency", "-d", "svg"])
+        .args(["--stdin-format", "json", "--output"])
+        .arg(&output)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("binary starts");
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(json.as_bytes())
+        .expect("stdin is writable");
+    let result = child.wait_with_output().expect("binary finishes");
+    (result, output.with_extension("svg"))
+}
+
+#[test]
+fn piped_json_is_drawn() {
+    let (result, svg) = draw_json("piped", r#"{"dots": 3, "edges": [[0, 1], [1, 2]]}"#);
+    let text = std::fs::read_to_string(&svg);
+    let _ = std::fs::remove_file(&svg);
+
+    assert!(result.status.success(), "{result:?}");
+    let text = text.expect("svg is written");
+    assert_eq!(text.matches("<circle ").count(), 3);
+    assert_eq!(text.matches("<line ").count(), 2);
+}
+
+#[test]
+fn hostile_json_fails_cleanly() {
+    for (name, json) in [
+        ("nested", "[".repeat(200_000)),
+        ("huge", r#"{"dots": 1e15}"#.to_owned()),
+    ] {
+        let (result, svg) = draw_json(name, &json);
+        let stderr = String::from_utf8_lossy(&result.stderr);
+
+        assert_eq!(result.status.code(), Some(1), "{name}: {stderr}");
+        assert!(stderr.contains("invalid graph JSON"), "{name}: {stderr}");
+        assert!(!svg.exists(), "{name}");
+    }
+}
+
+#[test]
+fn seeded_random_graph_is_drawn_the_same() {
+    let draw = |run: usize, seed: &str| {
+        let output = std::env::temp_dir().join(format!("seeded_{run}_{}.png", std::process::id()));
+        let result = Command::new(env!("CARGO_BIN_EXE_graph-bridge"))
+            .args(["-g", "edges", "-d", "svg", "--random", "12", "0.3"])
+            .args(["--layout-seed", seed, "--output"])
+            .arg(&output)
+            .output()
+            .expect("binary runs");
+        assert!(result.status.success(), "{result:?}");
+        let svg = output.with_extension("svg");
+        let text = std::fs::read_to_string(&svg).expect("svg is written");
+        let _ = std::fs::remove_file(&svg);
+        text
+    };
+
+    let first = draw(0, "7");
+    assert_eq!(first, draw(1, "7"));
+    assert_ne!(first, draw(2, "8"));
+}